@@ -2,6 +2,7 @@ use anchor_syn::idl::Idl;
 use anyhow::{anyhow, Error, Result};
 use serde::{Deserialize, Serialize};
 use serum_common::client::Cluster;
+use solana_sdk::pubkey::Pubkey;
 use std::fs::{self, File};
 use std::io::prelude::*;
 use std::path::Path;
@@ -148,6 +149,26 @@ impl Program {
             ))
     }
 
+    // Returns the program id given to `declare_id!`, recorded in the IDL
+    // metadata when parsed.
+    pub fn declared_id(&self) -> Result<Pubkey> {
+        let address = self
+            .idl
+            .metadata
+            .as_ref()
+            .and_then(|metadata| metadata.get("address"))
+            .and_then(|address| address.as_str())
+            .ok_or_else(|| {
+                anyhow!(
+                    "Program {} must declare its id with declare_id!",
+                    self.lib_name
+                )
+            })?;
+        address
+            .parse()
+            .map_err(|_| anyhow!("Invalid program id: {}", address))
+    }
+
     pub fn binary_path(&self) -> PathBuf {
         std::env::current_dir()
             .expect("Must have current dir")
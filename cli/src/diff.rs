@@ -0,0 +1,656 @@
+//! Compares two versions of a program's IDL, classifying every difference as
+//! breaking, i.e., clients or accounts built against the old IDL no longer
+//! work with the new program, or additive.
+//!
+//! Breaking changes are expected to come with a major version bump of the
+//! IDL, following Cargo's semver compatibility rules, i.e., a bump of the
+//! leftmost non-zero component, e.g., `0.1.3` to `0.2.0`.
+
+use anchor_syn::codegen::program::{sighash, SIGHASH_GLOBAL_NAMESPACE, SIGHASH_STATE_NAMESPACE};
+use anchor_syn::idl::coder::{account_discriminator, flatten_accounts};
+use anchor_syn::idl::{
+    EnumFields, EnumVariant, Idl, IdlAccount, IdlErrorCode, IdlEvent, IdlField, IdlIx, IdlType,
+    IdlTypeDef, IdlTypeDefTy,
+};
+use heck::SnakeCase;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Additive,
+    Breaking,
+}
+
+#[derive(Debug, Clone)]
+pub struct Change {
+    pub severity: Severity,
+    pub description: String,
+}
+
+impl Change {
+    pub fn is_breaking(&self) -> bool {
+        self.severity == Severity::Breaking
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Additive => "additive",
+            Severity::Breaking => "breaking",
+        };
+        write!(f, "[{}] {}", severity, self.description)
+    }
+}
+
+// Collects the changes found while walking both IDLs.
+#[derive(Default)]
+struct Changes(Vec<Change>);
+
+impl Changes {
+    fn breaking(&mut self, description: String) {
+        self.0.push(Change {
+            severity: Severity::Breaking,
+            description,
+        });
+    }
+
+    fn additive(&mut self, description: String) {
+        self.0.push(Change {
+            severity: Severity::Additive,
+            description,
+        });
+    }
+}
+
+/// Returns every change from the `old` to the `new` IDL, breaking changes
+/// first. Breaking changes made without a major version bump are flagged by
+/// a breaking change of the version.
+pub fn diff(old: &Idl, new: &Idl) -> Vec<Change> {
+    let mut changes = Changes::default();
+
+    diff_ixs(
+        &mut changes,
+        "instruction",
+        SIGHASH_GLOBAL_NAMESPACE,
+        &old.instructions,
+        &new.instructions,
+    );
+    match (&old.state, &new.state) {
+        (None, None) => {}
+        (None, Some(_)) => changes.additive("state added".to_string()),
+        (Some(_), None) => changes.breaking("state removed".to_string()),
+        (Some(old_state), Some(new_state)) => {
+            diff_ty_def(
+                &mut changes,
+                &format!("state `{}`", new_state.strct.name),
+                &old_state.strct,
+                &new_state.strct,
+            );
+            diff_ixs(
+                &mut changes,
+                "state method",
+                SIGHASH_STATE_NAMESPACE,
+                &old_state.methods,
+                &new_state.methods,
+            );
+        }
+    }
+    diff_ty_defs(&mut changes, "account", &old.accounts, &new.accounts);
+    diff_ty_defs(&mut changes, "type", &old.types, &new.types);
+    diff_events(
+        &mut changes,
+        old.events.as_deref().unwrap_or_default(),
+        new.events.as_deref().unwrap_or_default(),
+    );
+    diff_errors(
+        &mut changes,
+        old.errors.as_deref().unwrap_or_default(),
+        new.errors.as_deref().unwrap_or_default(),
+    );
+
+    if changes.0.iter().any(Change::is_breaking) && !is_major_bump(&old.version, &new.version) {
+        changes.breaking(format!(
+            "version `{}` to `{}` isn't a major version bump, but the IDL has breaking changes",
+            old.version, new.version
+        ));
+    }
+
+    let mut changes = changes.0;
+    changes.sort_by_key(|c| !c.is_breaking());
+    changes
+}
+
+/// True if `new` is a greater version than `old` that's incompatible with
+/// it, i.e., it changes the leftmost non-zero component.
+pub fn is_major_bump(old: &str, new: &str) -> bool {
+    match (parse_version(old), parse_version(new)) {
+        (Some(old), Some(new)) => new > old && compatible_prefix(&new) != compatible_prefix(&old),
+        _ => false,
+    }
+}
+
+// Parses a `major.minor.patch` version, ignoring any pre-release or build
+// metadata. Missing minor and patch components are zero.
+fn parse_version(version: &str) -> Option<[u64; 3]> {
+    let version = version.split(|c| c == '-' || c == '+').next()?;
+    let mut components = version.split('.').map(|c| c.trim().parse::<u64>().ok());
+    let major = components.next()??;
+    let minor = components.next().unwrap_or(Some(0))?;
+    let patch = components.next().unwrap_or(Some(0))?;
+    match components.next() {
+        None => Some([major, minor, patch]),
+        Some(_) => None,
+    }
+}
+
+// Versions are compatible if they share the components up to and including
+// the leftmost non-zero one.
+fn compatible_prefix(version: &[u64; 3]) -> &[u64] {
+    let len = version
+        .iter()
+        .position(|c| *c != 0)
+        .map_or(3, |idx| idx + 1);
+    &version[..len]
+}
+
+fn diff_ixs(changes: &mut Changes, kind: &str, namespace: &str, old: &[IdlIx], new: &[IdlIx]) {
+    for old_ix in old {
+        match new.iter().find(|ix| ix.name == old_ix.name) {
+            None => changes.breaking(format!(
+                "{} `{}` removed, its discriminator {:?} is no longer recognized",
+                kind,
+                old_ix.name,
+                sighash(namespace, &old_ix.name.to_snake_case()),
+            )),
+            Some(new_ix) => {
+                let ctx = format!("{} `{}`", kind, new_ix.name);
+                diff_fields(changes, &ctx, "arg", &old_ix.args, &new_ix.args);
+                diff_accounts(
+                    changes,
+                    &ctx,
+                    &flatten_accounts(&old_ix.accounts),
+                    &flatten_accounts(&new_ix.accounts),
+                );
+            }
+        }
+    }
+    for new_ix in new {
+        if !old.iter().any(|ix| ix.name == new_ix.name) {
+            changes.additive(format!(
+                "{} `{}` added, with discriminator {:?}",
+                kind,
+                new_ix.name,
+                sighash(namespace, &new_ix.name.to_snake_case()),
+            ));
+        }
+    }
+}
+
+// Accounts are given to an instruction positionally, so any account added,
+// removed or moved breaks existing clients.
+fn diff_accounts(
+    changes: &mut Changes,
+    ctx: &str,
+    old: &[(String, &IdlAccount)],
+    new: &[(String, &IdlAccount)],
+) {
+    for (old_name, old_acc) in old {
+        match new.iter().find(|(name, _)| name == old_name) {
+            None => changes.breaking(format!("{}: account `{}` removed", ctx, old_name)),
+            Some((_, new_acc)) => {
+                match (old_acc.is_mut, new_acc.is_mut) {
+                    (false, true) => {
+                        changes.breaking(format!("{}: account `{}` is now writable", ctx, old_name))
+                    }
+                    (true, false) => changes.additive(format!(
+                        "{}: account `{}` is no longer writable",
+                        ctx, old_name
+                    )),
+                    _ => {}
+                }
+                match (old_acc.is_signer, new_acc.is_signer) {
+                    (false, true) => {
+                        changes.breaking(format!("{}: account `{}` is now a signer", ctx, old_name))
+                    }
+                    (true, false) => changes.additive(format!(
+                        "{}: account `{}` is no longer a signer",
+                        ctx, old_name
+                    )),
+                    _ => {}
+                }
+            }
+        }
+    }
+    for (new_name, _) in new {
+        if !old.iter().any(|(name, _)| name == new_name) {
+            changes.breaking(format!("{}: account `{}` added", ctx, new_name));
+        }
+    }
+    if is_reordered(
+        &old.iter().map(|(name, _)| name).collect::<Vec<_>>(),
+        &new.iter().map(|(name, _)| name).collect::<Vec<_>>(),
+    ) {
+        changes.breaking(format!("{}: accounts reordered", ctx));
+    }
+}
+
+// Fields are serialized in order, so any field added, removed, moved or
+// retyped changes the layout of the data.
+fn diff_fields(changes: &mut Changes, ctx: &str, kind: &str, old: &[IdlField], new: &[IdlField]) {
+    for old_f in old {
+        match new.iter().find(|f| f.name == old_f.name) {
+            None => changes.breaking(format!("{}: {} `{}` removed", ctx, kind, old_f.name)),
+            Some(new_f) => {
+                if old_f.ty != new_f.ty {
+                    changes.breaking(format!(
+                        "{}: {} `{}` changed type from {} to {}",
+                        ctx,
+                        kind,
+                        old_f.name,
+                        ty_name(&old_f.ty),
+                        ty_name(&new_f.ty),
+                    ));
+                }
+            }
+        }
+    }
+    for new_f in new {
+        if !old.iter().any(|f| f.name == new_f.name) {
+            changes.breaking(format!("{}: {} `{}` added", ctx, kind, new_f.name));
+        }
+    }
+    if is_reordered(
+        &old.iter().map(|f| &f.name).collect::<Vec<_>>(),
+        &new.iter().map(|f| &f.name).collect::<Vec<_>>(),
+    ) {
+        changes.breaking(format!("{}: {}s reordered", ctx, kind));
+    }
+}
+
+fn diff_ty_defs(changes: &mut Changes, kind: &str, old: &[IdlTypeDef], new: &[IdlTypeDef]) {
+    for old_ty in old {
+        match new.iter().find(|ty| ty.name == old_ty.name) {
+            None if kind == "account" => changes.breaking(format!(
+                "account `{}` removed, its discriminator {:?} is no longer recognized",
+                old_ty.name,
+                account_discriminator(&old_ty.name),
+            )),
+            None => changes.breaking(format!("{} `{}` removed", kind, old_ty.name)),
+            Some(new_ty) => diff_ty_def(
+                changes,
+                &format!("{} `{}`", kind, new_ty.name),
+                old_ty,
+                new_ty,
+            ),
+        }
+    }
+    for new_ty in new {
+        if !old.iter().any(|ty| ty.name == new_ty.name) {
+            changes.additive(format!("{} `{}` added", kind, new_ty.name));
+        }
+    }
+}
+
+fn diff_ty_def(changes: &mut Changes, ctx: &str, old: &IdlTypeDef, new: &IdlTypeDef) {
+    match (&old.ty, &new.ty) {
+        (
+            IdlTypeDefTy::Struct {
+                generics: old_generics,
+                fields: old_fields,
+            },
+            IdlTypeDefTy::Struct {
+                generics: new_generics,
+                fields: new_fields,
+            },
+        ) => {
+            diff_generics(changes, ctx, old_generics, new_generics);
+            diff_fields(changes, ctx, "field", old_fields, new_fields);
+        }
+        (
+            IdlTypeDefTy::Tuple {
+                generics: old_generics,
+                fields: old_fields,
+            },
+            IdlTypeDefTy::Tuple {
+                generics: new_generics,
+                fields: new_fields,
+            },
+        ) => {
+            diff_generics(changes, ctx, old_generics, new_generics);
+            if old_fields != new_fields {
+                changes.breaking(format!("{}: tuple fields changed", ctx));
+            }
+        }
+        (
+            IdlTypeDefTy::Enum {
+                generics: old_generics,
+                variants: old_variants,
+            },
+            IdlTypeDefTy::Enum {
+                generics: new_generics,
+                variants: new_variants,
+            },
+        ) => {
+            diff_generics(changes, ctx, old_generics, new_generics);
+            diff_variants(changes, ctx, old_variants, new_variants);
+        }
+        _ => changes.breaking(format!(
+            "{}: changed from a {} to a {}",
+            ctx,
+            ty_def_kind(&old.ty),
+            ty_def_kind(&new.ty)
+        )),
+    }
+}
+
+fn diff_generics(changes: &mut Changes, ctx: &str, old: &[String], new: &[String]) {
+    if old != new {
+        changes.breaking(format!(
+            "{}: generics changed from <{}> to <{}>",
+            ctx,
+            old.join(", "),
+            new.join(", ")
+        ));
+    }
+}
+
+// Variants are serialized by their index, so only appending a variant keeps
+// existing data valid, while inserting one shifts every variant after it.
+fn diff_variants(changes: &mut Changes, ctx: &str, old: &[EnumVariant], new: &[EnumVariant]) {
+    for (idx, old_v) in old.iter().enumerate() {
+        match new.iter().position(|v| v.name == old_v.name) {
+            None => changes.breaking(format!("{}: variant `{}` removed", ctx, old_v.name)),
+            Some(new_idx) => {
+                if new_idx != idx {
+                    changes.breaking(format!(
+                        "{}: variant `{}` moved from index {} to {}",
+                        ctx, old_v.name, idx, new_idx
+                    ));
+                }
+                let variant_ctx = format!("{}: variant `{}`", ctx, old_v.name);
+                match (&old_v.fields, &new[new_idx].fields) {
+                    (None, None) => {}
+                    (Some(EnumFields::Named(old_fields)), Some(EnumFields::Named(new_fields))) => {
+                        diff_fields(changes, &variant_ctx, "field", old_fields, new_fields)
+                    }
+                    (Some(EnumFields::Tuple(old_fields)), Some(EnumFields::Tuple(new_fields)))
+                        if old_fields == new_fields => {}
+                    _ => changes.breaking(format!("{}: fields changed", variant_ctx)),
+                }
+            }
+        }
+    }
+    for (idx, new_v) in new.iter().enumerate() {
+        if old.iter().any(|v| v.name == new_v.name) {
+            continue;
+        }
+        if idx < old.len() {
+            changes.breaking(format!(
+                "{}: variant `{}` inserted at index {}",
+                ctx, new_v.name, idx
+            ));
+        } else {
+            changes.additive(format!("{}: variant `{}` added", ctx, new_v.name));
+        }
+    }
+}
+
+fn diff_events(changes: &mut Changes, old: &[IdlEvent], new: &[IdlEvent]) {
+    for old_ev in old {
+        match new.iter().find(|ev| ev.name == old_ev.name) {
+            None => changes.breaking(format!("event `{}` removed", old_ev.name)),
+            Some(new_ev) => diff_fields(
+                changes,
+                &format!("event `{}`", new_ev.name),
+                "field",
+                &old_ev.fields,
+                &new_ev.fields,
+            ),
+        }
+    }
+    for new_ev in new {
+        if !old.iter().any(|ev| ev.name == new_ev.name) {
+            changes.additive(format!("event `{}` added", new_ev.name));
+        }
+    }
+}
+
+// Clients map error codes to names, so renumbering an error breaks them.
+fn diff_errors(changes: &mut Changes, old: &[IdlErrorCode], new: &[IdlErrorCode]) {
+    for old_err in old {
+        match new.iter().find(|e| e.name == old_err.name) {
+            None => changes.breaking(format!(
+                "error `{}` ({}) removed",
+                old_err.name, old_err.code
+            )),
+            Some(new_err) => {
+                if new_err.code != old_err.code {
+                    changes.breaking(format!(
+                        "error `{}` changed code from {} to {}",
+                        old_err.name, old_err.code, new_err.code
+                    ));
+                }
+            }
+        }
+    }
+    for new_err in new {
+        if !old.iter().any(|e| e.name == new_err.name) {
+            changes.additive(format!("error `{}` ({}) added", new_err.name, new_err.code));
+        }
+    }
+}
+
+// True if the names found in both lists appear in a different order.
+fn is_reordered(old: &[&String], new: &[&String]) -> bool {
+    let old_common = old.iter().filter(|name| new.contains(name));
+    let new_common = new.iter().filter(|name| old.contains(name));
+    !old_common.eq(new_common)
+}
+
+fn ty_def_kind(ty: &IdlTypeDefTy) -> &'static str {
+    match ty {
+        IdlTypeDefTy::Struct { .. } => "struct",
+        IdlTypeDefTy::Tuple { .. } => "tuple struct",
+        IdlTypeDefTy::Enum { .. } => "enum",
+    }
+}
+
+// Types are displayed as they appear in the IDL's JSON.
+fn ty_name(ty: &IdlType) -> String {
+    serde_json::to_string(ty).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    fn idl(version: &str, instructions: Value, types: Value) -> Idl {
+        serde_json::from_value(json!({
+            "version": version,
+            "name": "test",
+            "instructions": instructions,
+            "types": types,
+        }))
+        .unwrap()
+    }
+
+    fn ix(name: &str, accounts: Value, args: Value) -> Value {
+        json!({ "name": name, "accounts": accounts, "args": args })
+    }
+
+    fn account(name: &str, is_mut: bool, is_signer: bool) -> Value {
+        json!({ "name": name, "isMut": is_mut, "isSigner": is_signer })
+    }
+
+    fn enum_ty(variants: &[&str]) -> Value {
+        let variants: Vec<Value> = variants.iter().map(|v| json!({ "name": v })).collect();
+        json!([{ "name": "Kind", "type": { "kind": "enum", "variants": variants } }])
+    }
+
+    fn descriptions(changes: &[Change], severity: Severity) -> Vec<&str> {
+        changes
+            .iter()
+            .filter(|c| c.severity == severity)
+            .map(|c| c.description.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn identical_idls_have_no_changes() {
+        let old = idl(
+            "0.1.0",
+            json!([ix(
+                "initialize",
+                json!([account("data", true, false)]),
+                json!([{ "name": "amount", "type": "u64" }]),
+            )]),
+            enum_ty(&["A", "B"]),
+        );
+        assert!(diff(&old, &old).is_empty());
+    }
+
+    #[test]
+    fn additive_changes_keep_the_version_compatible() {
+        let old = idl("0.1.0", json!([ix("a", json!([]), json!([]))]), json!([]));
+        let new = idl(
+            "0.1.1",
+            json!([ix("a", json!([]), json!([])), ix("b", json!([]), json!([]))]),
+            json!([]),
+        );
+        let changes = diff(&old, &new);
+        assert!(descriptions(&changes, Severity::Breaking).is_empty());
+        assert_eq!(
+            descriptions(&changes, Severity::Additive),
+            vec![format!(
+                "instruction `b` added, with discriminator {:?}",
+                sighash(SIGHASH_GLOBAL_NAMESPACE, "b")
+            )]
+        );
+    }
+
+    #[test]
+    fn breaking_changes_require_a_major_version_bump() {
+        let old = idl(
+            "0.1.0",
+            json!([ix(
+                "a",
+                json!([account("from", false, true), account("to", false, false)]),
+                json!([{ "name": "amount", "type": "u64" }]),
+            )]),
+            json!([]),
+        );
+        let new = |version| {
+            idl(
+                version,
+                json!([ix(
+                    "a",
+                    json!([account("to", true, false), account("from", false, true)]),
+                    json!([{ "name": "amount", "type": "u32" }]),
+                )]),
+                json!([]),
+            )
+        };
+
+        let changes = diff(&old, &new("0.1.1"));
+        assert_eq!(
+            descriptions(&changes, Severity::Breaking),
+            vec![
+                "instruction `a`: arg `amount` changed type from \"u64\" to \"u32\"",
+                "instruction `a`: account `to` is now writable",
+                "instruction `a`: accounts reordered",
+                "version `0.1.0` to `0.1.1` isn't a major version bump, but the IDL has breaking changes",
+            ]
+        );
+
+        let changes = diff(&old, &new("0.2.0"));
+        assert_eq!(descriptions(&changes, Severity::Breaking).len(), 3);
+    }
+
+    #[test]
+    fn composite_accounts_are_compared_by_path() {
+        let old = idl(
+            "1.0.0",
+            json!([ix(
+                "a",
+                json!([{ "name": "inner", "accounts": [account("vault", false, false)] }]),
+                json!([]),
+            )]),
+            json!([]),
+        );
+        let new = idl(
+            "2.0.0",
+            json!([ix("a", json!([account("vault", false, false)]), json!([]))]),
+            json!([]),
+        );
+        assert_eq!(
+            descriptions(&diff(&old, &new), Severity::Breaking),
+            vec![
+                "instruction `a`: account `inner.vault` removed",
+                "instruction `a`: account `vault` added",
+            ]
+        );
+    }
+
+    #[test]
+    fn appended_variants_are_additive() {
+        let old = idl("0.1.0", json!([]), enum_ty(&["A", "B"]));
+        let new = idl("0.1.0", json!([]), enum_ty(&["A", "B", "C"]));
+        let changes = diff(&old, &new);
+        assert_eq!(
+            descriptions(&changes, Severity::Additive),
+            vec!["type `Kind`: variant `C` added"]
+        );
+        assert!(descriptions(&changes, Severity::Breaking).is_empty());
+    }
+
+    #[test]
+    fn inserted_variants_are_breaking() {
+        let old = idl("0.1.0", json!([]), enum_ty(&["A", "B"]));
+        let new = idl("0.2.0", json!([]), enum_ty(&["A", "C", "B"]));
+        assert_eq!(
+            descriptions(&diff(&old, &new), Severity::Breaking),
+            vec![
+                "type `Kind`: variant `B` moved from index 1 to 2",
+                "type `Kind`: variant `C` inserted at index 1",
+            ]
+        );
+    }
+
+    #[test]
+    fn renumbered_errors_are_breaking() {
+        let mut old = idl("0.1.0", json!([]), json!([]));
+        let mut new = old.clone();
+        old.errors = serde_json::from_value(json!([{ "code": 100, "name": "Bad" }])).unwrap();
+        new.errors = serde_json::from_value(json!([
+            { "code": 101, "name": "Bad" },
+            { "code": 102, "name": "Worse" },
+        ]))
+        .unwrap();
+        let changes = diff(&old, &new);
+        assert_eq!(
+            descriptions(&changes, Severity::Breaking),
+            vec![
+                "error `Bad` changed code from 100 to 101",
+                "version `0.1.0` to `0.1.0` isn't a major version bump, but the IDL has breaking changes",
+            ]
+        );
+        assert_eq!(
+            descriptions(&changes, Severity::Additive),
+            vec!["error `Worse` (102) added"]
+        );
+    }
+
+    #[test]
+    fn major_version_bumps() {
+        assert!(is_major_bump("0.1.3", "0.2.0"));
+        assert!(is_major_bump("1.4.0", "2.0.0"));
+        assert!(is_major_bump("0.0.1", "0.0.2"));
+        assert!(is_major_bump("0.1.0", "1.0.0-beta.1"));
+        assert!(!is_major_bump("0.1.0", "0.1.9"));
+        assert!(!is_major_bump("1.0.0", "1.3.0"));
+        assert!(!is_major_bump("0.2.0", "0.1.0"));
+        assert!(!is_major_bump("0.1.0", "latest"));
+    }
+}
@@ -1,12 +1,13 @@
 use crate::config::{read_all_programs, Config, Program};
 use anchor_lang::idl::IdlAccount;
 use anchor_lang::{AccountDeserialize, AnchorDeserialize, AnchorSerialize};
-use anchor_syn::idl::Idl;
+use anchor_syn::idl::{Idl, IdlErrorCode, IdlEvent, IdlTypeDef};
 use anyhow::{anyhow, Result};
 use clap::Clap;
 use flate2::read::ZlibDecoder;
 use flate2::write::ZlibEncoder;
 use flate2::Compression;
+use heck::SnakeCase;
 use rand::rngs::OsRng;
 use serde::{Deserialize, Serialize};
 use solana_client::rpc_client::RpcClient;
@@ -24,6 +25,7 @@ use std::process::{Child, Stdio};
 use std::string::ToString;
 
 mod config;
+mod diff;
 mod template;
 
 #[derive(Debug, Clap)]
@@ -62,6 +64,10 @@ pub enum Command {
         url: Option<String>,
         #[clap(short, long)]
         keypair: Option<String>,
+        /// Generates a keypair for programs missing one, and declares its
+        /// address in place of the program's old id.
+        #[clap(long)]
+        new_program_id: bool,
     },
     /// Runs the deploy migration script.
     Migrate {
@@ -74,6 +80,10 @@ pub enum Command {
         url: Option<String>,
         #[clap(short, long)]
         keypair: Option<String>,
+        /// Generates a keypair for programs missing one, and declares its
+        /// address in place of the program's old id.
+        #[clap(long)]
+        new_program_id: bool,
     },
     /// Upgrades a single program. The configured wallet must be the upgrade
     /// authority.
@@ -89,6 +99,23 @@ pub enum Command {
         #[clap(short, long)]
         url: Option<String>,
     },
+    /// Fetches an account and decodes its data with the program's IDL.
+    Account {
+        /// Type of the account, as <Program>.<AccountType>.
+        account_type: String,
+        /// Address of the account.
+        address: Pubkey,
+        /// IDL to decode the account with. If not given, the workspace's IDL
+        /// for the program is used, or else the IDL stored on chain by the
+        /// account's owner. Outside a workspace, the default cluster is used.
+        #[clap(long)]
+        idl: Option<String>,
+    },
+    /// Decodes instructions and transactions with the programs' IDLs.
+    Decode {
+        #[clap(subcommand)]
+        subcmd: DecodeCommand,
+    },
 }
 
 #[derive(Debug, Clap)]
@@ -99,11 +126,16 @@ pub enum IdlCommand {
         #[clap(short, long)]
         filepath: String,
     },
-    /// Upgrades the IDL to the new file.
+    /// Upgrades the IDL to the new file. Refuses to if the new IDL has
+    /// breaking changes from the one on chain without a major version bump,
+    /// unless forced.
     Upgrade {
         program_id: Pubkey,
         #[clap(short, long)]
         filepath: String,
+        /// Upgrade even if the new IDL breaks existing clients.
+        #[clap(long)]
+        force: bool,
     },
     /// Sets a new authority on the IDL account.
     SetAuthority {
@@ -142,6 +174,42 @@ pub enum IdlCommand {
         #[clap(short, long)]
         out: Option<String>,
     },
+    /// Compares two IDLs, classifying each change as breaking or additive.
+    Diff {
+        /// Path to the old IDL.
+        old: String,
+        /// Path to the new IDL.
+        new: String,
+    },
+}
+
+#[derive(Debug, Clap)]
+pub enum DecodeCommand {
+    /// Decodes instruction data.
+    Ix {
+        /// Base58 encoded instruction data, or base64 with --base64.
+        data: String,
+        /// IDL of the program executing the instruction.
+        #[clap(short, long)]
+        idl: String,
+        /// Accounts given to the instruction, in order, to label with their
+        /// names in the IDL.
+        #[clap(short, long)]
+        accounts: Vec<Pubkey>,
+        #[clap(long)]
+        base64: bool,
+    },
+    /// Decodes every instruction of a serialized transaction.
+    Tx {
+        /// Base58 encoded transaction, or base64 with --base64.
+        tx: String,
+        /// IDLs of the programs invoked, matched by the address in their
+        /// metadata. Defaults to the IDLs of the workspace's programs.
+        #[clap(short, long)]
+        idl: Vec<String>,
+        #[clap(long)]
+        base64: bool,
+    },
 }
 
 fn main() -> Result<()> {
@@ -150,16 +218,30 @@ fn main() -> Result<()> {
         Command::Init { name } => init(name),
         Command::New { name } => new(name),
         Command::Build { idl } => build(idl),
-        Command::Deploy { url, keypair } => deploy(url, keypair),
+        Command::Deploy {
+            url,
+            keypair,
+            new_program_id,
+        } => deploy(url, keypair, new_program_id),
         Command::Upgrade {
             program_id,
             program_filepath,
         } => upgrade(program_id, program_filepath),
         Command::Idl { subcmd } => idl(subcmd),
         Command::Migrate { url } => migrate(url),
-        Command::Launch { url, keypair } => launch(url, keypair),
+        Command::Launch {
+            url,
+            keypair,
+            new_program_id,
+        } => launch(url, keypair, new_program_id),
         Command::Test { skip_deploy } => test(skip_deploy),
         Command::Airdrop { url } => airdrop(url),
+        Command::Account {
+            account_type,
+            address,
+            idl,
+        } => account(account_type, address, idl),
+        Command::Decode { subcmd } => decode(subcmd),
     }
 }
 
@@ -228,8 +310,18 @@ fn new_program(name: &str) -> Result<()> {
     cargo_toml.write_all(template::cargo_toml(&name).as_bytes())?;
     let mut xargo_toml = File::create(&format!("programs/{}/Xargo.toml", name))?;
     xargo_toml.write_all(template::xargo_toml().as_bytes())?;
+
+    // Generate the program's keypair, declaring its address in lib.rs.
+    let program_kp = Keypair::generate(&mut OsRng);
+    fs::create_dir_all("target/deploy")?;
+    let mut keypair_file = File::create(&format!(
+        "target/deploy/anchor-{}-keypair.json",
+        name.to_snake_case()
+    ))?;
+    keypair_file.write_all(format!("{:?}", &program_kp.to_bytes()).as_bytes())?;
+
     let mut lib_rs = File::create(&format!("programs/{}/src/lib.rs", name))?;
-    lib_rs.write_all(template::lib_rs(&name).as_bytes())?;
+    lib_rs.write_all(template::lib_rs(&name, &program_kp.pubkey()).as_bytes())?;
     Ok(())
 }
 
@@ -287,8 +379,12 @@ fn build_cwd(cargo_toml: PathBuf, idl_out: Option<PathBuf>) -> Result<()> {
         std::process::exit(exit.status.code().unwrap_or(1));
     }
 
-    // Always assume idl is located ar src/lib.rs.
-    let idl = extract_idl("src/lib.rs")?;
+    // Always assume idl is located ar src/lib.rs. Programs with the
+    // `idl-build` feature have their IDL emitted by the macros instead.
+    let idl = match has_feature("Cargo.toml", "idl-build")? {
+        true => build_idl()?,
+        false => extract_idl("src/lib.rs")?,
+    };
 
     let out = match idl_out {
         None => PathBuf::from(".").join(&idl.name).with_extension("json"),
@@ -300,7 +396,7 @@ fn build_cwd(cargo_toml: PathBuf, idl_out: Option<PathBuf>) -> Result<()> {
 
 // Fetches an IDL for the given program_id.
 fn fetch_idl(program_id: Pubkey) -> Result<Idl> {
-    let cfg = Config::discover()?.expect("Inside a workspace").0;
+    let cfg = discover_config()?;
     let client = RpcClient::new(cfg.cluster.url().to_string());
 
     let idl_addr = IdlAccount::address(&program_id);
@@ -325,6 +421,91 @@ fn extract_idl(file: &str) -> Result<Idl> {
     anchor_syn::parser::file::parse(&*file)
 }
 
+// Returns true if the crate's Cargo.toml declares the given feature.
+fn has_feature(cargo_toml: impl AsRef<Path>, feature: &str) -> Result<bool> {
+    let cargo_toml: toml::Value = fs::read_to_string(cargo_toml)?.parse()?;
+    Ok(cargo_toml
+        .get("features")
+        .and_then(|features| features.get(feature))
+        .is_some())
+}
+
+// Builds the IDL of the crate in the current directory by running the tests
+// generated under its `idl-build` feature, each printing a fragment of the
+// IDL.
+fn build_idl() -> Result<Idl> {
+    let exit = std::process::Command::new("cargo")
+        .arg("test")
+        .arg("--features")
+        .arg("idl-build")
+        .arg("__anchor_private_print_idl")
+        .arg("--")
+        .arg("--show-output")
+        .arg("--test-threads=1")
+        .stderr(Stdio::inherit())
+        .output()
+        .map_err(|e| anyhow::format_err!("{}", e.to_string()))?;
+    if !exit.status.success() {
+        std::process::exit(exit.status.code().unwrap_or(1));
+    }
+    let stdout = String::from_utf8(exit.stdout)?;
+
+    let mut idl: Option<Idl> = None;
+    let mut accounts: Vec<IdlTypeDef> = vec![];
+    let mut types: Vec<IdlTypeDef> = vec![];
+    let mut events: Vec<IdlEvent> = vec![];
+    let mut errors: Vec<IdlErrorCode> = vec![];
+    let mut address = None;
+    for line in stdout.lines() {
+        let fragment = match line.find(anchor_syn::idl::IDL_FRAGMENT_PREFIX) {
+            None => continue,
+            Some(i) => &line[i + anchor_syn::idl::IDL_FRAGMENT_PREFIX.len()..],
+        };
+        let mut fragment = fragment.trim().splitn(2, ' ');
+        let kind = fragment.next().unwrap_or_default();
+        let json = fragment
+            .next()
+            .ok_or_else(|| anyhow!("Invalid IDL fragment: {}", kind))?;
+        match kind {
+            "program" => idl = Some(serde_json::from_str(json)?),
+            "account" => accounts.push(serde_json::from_str(json)?),
+            "type" => types.push(serde_json::from_str(json)?),
+            "event" => events.push(serde_json::from_str(json)?),
+            "errors" => errors = serde_json::from_str(json)?,
+            "address" => address = Some(serde_json::from_str::<String>(json)?),
+            _ => return Err(anyhow!("Unknown IDL fragment: {}", kind)),
+        }
+    }
+    let mut idl = idl.ok_or_else(|| anyhow!("#[program] not found in the test output"))?;
+
+    // The state struct is an account under the hood, but is a type in the IDL.
+    let state_name = idl.state.as_ref().map(|s| s.strct.name.clone());
+    idl.accounts = accounts
+        .into_iter()
+        .filter(|acc| Some(&acc.name) != state_name.as_ref())
+        .collect();
+    // Types deriving both `AnchorSerialize` and `AnchorDeserialize` are
+    // printed twice, and accounts and events derive them too.
+    for ty in types {
+        let is_dup = idl.types.iter().any(|t| t.name == ty.name)
+            || idl.accounts.iter().any(|acc| acc.name == ty.name)
+            || events.iter().any(|event| event.name == ty.name);
+        if !is_dup {
+            idl.types.push(ty);
+        }
+    }
+    if !events.is_empty() {
+        idl.events = Some(events);
+    }
+    let mut error_codes = anchor_syn::parser::file::idl_error_codes(None);
+    error_codes.append(&mut errors);
+    idl.errors = Some(error_codes);
+    if let Some(address) = address {
+        idl.metadata = Some(serde_json::to_value(IdlMetadata { address })?);
+    }
+    Ok(idl)
+}
+
 fn idl(subcmd: IdlCommand) -> Result<()> {
     match subcmd {
         IdlCommand::Init {
@@ -334,7 +515,8 @@ fn idl(subcmd: IdlCommand) -> Result<()> {
         IdlCommand::Upgrade {
             program_id,
             filepath,
-        } => idl_upgrade(program_id, filepath),
+            force,
+        } => idl_upgrade(program_id, filepath, force),
         IdlCommand::SetAuthority {
             program_id,
             new_authority,
@@ -343,6 +525,7 @@ fn idl(subcmd: IdlCommand) -> Result<()> {
         IdlCommand::Authority { program_id } => idl_authority(program_id),
         IdlCommand::Parse { file, out } => idl_parse(file, out),
         IdlCommand::Fetch { program_id, out } => idl_fetch(program_id, out),
+        IdlCommand::Diff { old, new } => idl_diff(old, new),
     }
 }
 
@@ -360,11 +543,28 @@ fn idl_init(program_id: Pubkey, idl_filepath: String) -> Result<()> {
     })
 }
 
-fn idl_upgrade(program_id: Pubkey, idl_filepath: String) -> Result<()> {
+fn idl_upgrade(program_id: Pubkey, idl_filepath: String, force: bool) -> Result<()> {
     with_workspace(|cfg, _path, _cargo| {
         let bytes = std::fs::read(idl_filepath)?;
         let idl: Idl = serde_json::from_reader(&*bytes)?;
 
+        if !force {
+            let old_idl = fetch_idl(program_id)?;
+            let breaking: Vec<diff::Change> = diff::diff(&old_idl, &idl)
+                .into_iter()
+                .filter(|c| c.is_breaking())
+                .collect();
+            for change in &breaking {
+                println!("{}", change);
+            }
+            if !breaking.is_empty() && !diff::is_major_bump(&old_idl.version, &idl.version) {
+                return Err(anyhow!(
+                    "The new IDL has {} breaking change(s) without a major version bump, use --force to upgrade anyway",
+                    breaking.len()
+                ));
+            }
+        }
+
         idl_clear(cfg, &program_id)?;
         idl_write(cfg, &program_id, &idl)?;
 
@@ -568,6 +768,205 @@ fn idl_fetch(program_id: Pubkey, out: Option<String>) -> Result<()> {
     write_idl(&idl, out)
 }
 
+fn idl_diff(old: String, new: String) -> Result<()> {
+    let old: Idl = serde_json::from_reader(&*std::fs::read(old)?)?;
+    let new: Idl = serde_json::from_reader(&*std::fs::read(new)?)?;
+    if old.version != new.version {
+        println!("Version: {} -> {}", old.version, new.version);
+    }
+    let changes = diff::diff(&old, &new);
+    for change in &changes {
+        println!("{}", change);
+    }
+    let breaking = changes.iter().filter(|c| c.is_breaking()).count();
+    println!(
+        "{} breaking, {} additive change(s)",
+        breaking,
+        changes.len() - breaking
+    );
+    Ok(())
+}
+
+fn account(account_type: String, address: Pubkey, idl_filepath: Option<String>) -> Result<()> {
+    let (program_name, account_name) = {
+        let mut parts = account_type.splitn(2, '.');
+        match (parts.next(), parts.next()) {
+            (Some(program_name), Some(account_name)) => {
+                (program_name.to_snake_case(), account_name.to_string())
+            }
+            _ => {
+                return Err(anyhow!(
+                    "Account type must be given as <Program>.<AccountType>"
+                ))
+            }
+        }
+    };
+    let cfg = discover_config()?;
+    let client = RpcClient::new(cfg.cluster.url().to_string());
+    let account = client
+        .get_account_with_commitment(&address, CommitmentConfig::recent())?
+        .value
+        .map_or(Err(anyhow!("Account not found")), Ok)?;
+
+    let idl: Idl = match idl_filepath {
+        Some(idl_filepath) => serde_json::from_reader(&*std::fs::read(idl_filepath)?)?,
+        None => match workspace_idl(&program_name) {
+            Some(idl) => idl,
+            None => fetch_idl(account.owner)?,
+        },
+    };
+
+    let data = anchor_syn::idl::coder::decode_account(&idl, &account_name, &account.data)?;
+    println!("{}", serde_json::to_string_pretty(&data)?);
+
+    Ok(())
+}
+
+// Returns the IDL of the workspace's program with the given lib name, if run
+// within a workspace containing it. Programs that fail to parse are reported,
+// so that the caller can fall back to another IDL.
+fn workspace_idl(lib_name: &str) -> Option<Idl> {
+    if !matches!(Config::discover(), Ok(Some(_))) {
+        return None;
+    }
+    with_workspace(|_cfg, _path, _cargo| match read_all_programs() {
+        Ok(programs) => programs
+            .into_iter()
+            .find(|program| program.lib_name == lib_name)
+            .map(|program| program.idl),
+        Err(e) => {
+            eprintln!("Unable to read the workspace's programs: {}", e);
+            None
+        }
+    })
+}
+
+// Returns the workspace's config, or the default config outside of one.
+fn discover_config() -> Result<Config> {
+    Ok(Config::discover()?
+        .map(|(cfg, _path, _cargo)| cfg)
+        .unwrap_or_default())
+}
+
+fn decode(subcmd: DecodeCommand) -> Result<()> {
+    match subcmd {
+        DecodeCommand::Ix {
+            data,
+            idl,
+            accounts,
+            base64,
+        } => decode_ix(data, idl, accounts, base64),
+        DecodeCommand::Tx { tx, idl, base64 } => decode_tx(tx, idl, base64),
+    }
+}
+
+fn decode_ix(
+    data: String,
+    idl_filepath: String,
+    accounts: Vec<Pubkey>,
+    base64: bool,
+) -> Result<()> {
+    let idl: Idl = serde_json::from_reader(&*std::fs::read(idl_filepath)?)?;
+    let data = decode_str(&data, base64)?;
+    let ix = decoded_ix_json(&idl, &data, &accounts)?;
+    println!("{}", serde_json::to_string_pretty(&ix)?);
+    Ok(())
+}
+
+fn decode_tx(tx: String, idl_filepaths: Vec<String>, base64: bool) -> Result<()> {
+    let tx: Transaction = bincode::deserialize(&decode_str(&tx, base64)?)?;
+    let idls: Vec<Idl> = match idl_filepaths.is_empty() {
+        true => with_workspace(|_cfg, _path, _cargo| -> Result<Vec<Idl>> {
+            Ok(read_all_programs()?
+                .into_iter()
+                .map(|program| program.idl)
+                .collect())
+        })?,
+        false => idl_filepaths
+            .iter()
+            .map(|idl_filepath| Ok(serde_json::from_reader(&*std::fs::read(idl_filepath)?)?))
+            .collect::<Result<_>>()?,
+    };
+    let find_idl = |program_id: &Pubkey| {
+        idls.iter().find(|idl| {
+            idl.metadata
+                .as_ref()
+                .and_then(|m| serde_json::from_value::<IdlMetadata>(m.clone()).ok())
+                .map(|m| m.address == program_id.to_string())
+                .unwrap_or(false)
+        })
+    };
+
+    let ixs = tx
+        .message
+        .instructions
+        .iter()
+        .map(|ix| {
+            // Malformed transactions may index past the account keys, which
+            // is reported per instruction.
+            let account_key = |idx: u8| {
+                tx.message
+                    .account_keys
+                    .get(idx as usize)
+                    .ok_or_else(|| anyhow!("Account index {} out of bounds", idx))
+            };
+            let program_id = account_key(ix.program_id_index);
+            let program = program_id.as_ref().ok().map(|p| p.to_string());
+            let decoded = program_id.and_then(|program_id| {
+                let accounts = ix
+                    .accounts
+                    .iter()
+                    .map(|idx| account_key(*idx).map(|key| *key))
+                    .collect::<Result<Vec<Pubkey>>>()?;
+                match find_idl(program_id) {
+                    None => Err(anyhow!("IDL not found")),
+                    Some(idl) => decoded_ix_json(idl, &ix.data, &accounts),
+                }
+            });
+            match decoded {
+                Ok(mut decoded) => {
+                    decoded["program"] = serde_json::json!(program);
+                    decoded
+                }
+                Err(e) => serde_json::json!({
+                    "program": program,
+                    "error": e.to_string(),
+                }),
+            }
+        })
+        .collect::<Vec<serde_json::Value>>();
+    println!("{}", serde_json::to_string_pretty(&ixs)?);
+    Ok(())
+}
+
+// Labels each account with its name in the IDL. Any accounts beyond those in
+// the IDL are the instruction's remaining accounts, and are left unnamed.
+fn decoded_ix_json(idl: &Idl, data: &[u8], accounts: &[Pubkey]) -> Result<serde_json::Value> {
+    let ix = anchor_syn::idl::coder::decode_instruction(idl, data)?;
+    let accounts: Vec<serde_json::Value> = accounts
+        .iter()
+        .enumerate()
+        .map(|(idx, pubkey)| {
+            serde_json::json!({
+                "name": ix.accounts.get(idx),
+                "pubkey": pubkey.to_string(),
+            })
+        })
+        .collect();
+    Ok(serde_json::json!({
+        "name": ix.name,
+        "args": ix.args,
+        "accounts": accounts,
+    }))
+}
+
+fn decode_str(s: &str, is_base64: bool) -> Result<Vec<u8>> {
+    match is_base64 {
+        true => Ok(base64::decode(s)?),
+        false => Ok(bs58::decode(s).into_vec()?),
+    }
+}
+
 fn write_idl(idl: &Idl, out: OutFile) -> Result<()> {
     let idl_json = serde_json::to_string_pretty(idl)?;
     match out {
@@ -597,7 +996,7 @@ fn test(skip_deploy: bool) -> Result<()> {
             }
             _ => {
                 if !skip_deploy {
-                    deploy(None, None)?;
+                    deploy(None, None, false)?;
                 }
                 None
             }
@@ -634,27 +1033,16 @@ fn test(skip_deploy: bool) -> Result<()> {
 }
 
 // Returns the solana-test-validator flags to embed the workspace programs
-// in the genesis block. This allows us to run tests without every deploying.
+// in the genesis block, at their declared addresses. This allows us to run
+// tests without every deploying.
 fn genesis_flags() -> Result<Vec<String>> {
     let mut flags = Vec::new();
-    for mut program in read_all_programs()? {
+    for program in read_all_programs()? {
         let binary_path = program.binary_path().display().to_string();
 
-        let kp = Keypair::generate(&mut OsRng);
-        let address = kp.pubkey().to_string();
-
         flags.push("--bpf-program".to_string());
-        flags.push(address.clone());
+        flags.push(program.declared_id()?.to_string());
         flags.push(binary_path);
-
-        // Add program address to the IDL.
-        program.idl.metadata = Some(serde_json::to_value(IdlTestMetadata { address })?);
-
-        // Persist it.
-        let idl_out = PathBuf::from("target/idl")
-            .join(&program.idl.name)
-            .with_extension("json");
-        write_idl(&program.idl, OutFile::File(idl_out))?;
     }
     Ok(flags)
 }
@@ -674,7 +1062,7 @@ fn stream_logs(url: &str) -> Result<Vec<std::process::Child>> {
         let metadata = idl
             .metadata
             .ok_or_else(|| anyhow!("Program address not found."))?;
-        let metadata: IdlTestMetadata = serde_json::from_value(metadata)?;
+        let metadata: IdlMetadata = serde_json::from_value(metadata)?;
 
         let log_file = File::create(format!(
             "{}/{}.{}.log",
@@ -694,8 +1082,8 @@ fn stream_logs(url: &str) -> Result<Vec<std::process::Child>> {
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-pub struct IdlTestMetadata {
-    address: String,
+pub struct IdlMetadata {
+    pub address: String,
 }
 
 fn start_test_validator(flags: Option<Vec<String>>) -> Result<Child> {
@@ -742,14 +1130,21 @@ fn start_test_validator(flags: Option<Vec<String>>) -> Result<Child> {
     Ok(validator_handle)
 }
 
-// TODO: Testing and deploys should use separate sections of metadata.
-//       Similarly, each network should have separate metadata.
-fn deploy(url: Option<String>, keypair: Option<String>) -> Result<()> {
-    _deploy(url, keypair).map(|_| ())
+fn deploy(url: Option<String>, keypair: Option<String>, new_program_id: bool) -> Result<()> {
+    _deploy(url, keypair, new_program_id).map(|_| ())
 }
 
-fn _deploy(url: Option<String>, keypair: Option<String>) -> Result<Vec<(Pubkey, Program)>> {
+fn _deploy(
+    url: Option<String>,
+    keypair: Option<String>,
+    new_program_id: bool,
+) -> Result<Vec<(Pubkey, Program)>> {
     with_workspace(|cfg, _path, _cargo| {
+        if new_program_id {
+            for program in read_all_programs()? {
+                generate_missing_keypair(&program)?;
+            }
+        }
         build(None)?;
 
         // Fallback to config vars if not provided via CLI.
@@ -762,16 +1157,38 @@ fn _deploy(url: Option<String>, keypair: Option<String>) -> Result<Vec<(Pubkey,
 
         let mut programs = Vec::new();
 
-        for mut program in read_all_programs()? {
+        for program in read_all_programs()? {
             let binary_path = program.binary_path().display().to_string();
 
             println!("Deploying {}...", binary_path);
 
-            // Write the program's keypair filepath. This forces a new deploy
-            // address.
-            let program_kp = Keypair::generate(&mut OsRng);
-            let mut file = File::create(program.anchor_keypair_path())?;
-            file.write_all(format!("{:?}", &program_kp.to_bytes()).as_bytes())?;
+            // The program is deployed to the address given by `declare_id!`,
+            // so its keypair must be the one generated with the program.
+            let program_id = program.declared_id()?;
+            let keypair_path = program.anchor_keypair_path();
+            if !keypair_path.exists() {
+                return Err(anyhow!(
+                    "Program keypair {} not found. Copy in the keypair of the declared \
+                     program id {}, or pass --new-program-id to generate a new keypair \
+                     and declare its address instead",
+                    keypair_path.display(),
+                    program_id
+                ));
+            }
+            let program_kp =
+                solana_sdk::signature::read_keypair_file(&keypair_path).map_err(|_| {
+                    anyhow!(
+                        "Unable to read program keypair file: {}",
+                        keypair_path.display()
+                    )
+                })?;
+            if program_kp.pubkey() != program_id {
+                return Err(anyhow!(
+                    "Program keypair {} does not match the declared program id {}",
+                    keypair_path.display(),
+                    program_id
+                ));
+            }
 
             // Send deploy transactions.
             let exit = std::process::Command::new("solana")
@@ -782,7 +1199,7 @@ fn _deploy(url: Option<String>, keypair: Option<String>) -> Result<Vec<(Pubkey,
                 .arg("--keypair")
                 .arg(&keypair)
                 .arg("--program-id")
-                .arg(program.anchor_keypair_path().display().to_string())
+                .arg(keypair_path.display().to_string())
                 .arg(&binary_path)
                 .stdout(Stdio::inherit())
                 .stderr(Stdio::inherit())
@@ -793,18 +1210,7 @@ fn _deploy(url: Option<String>, keypair: Option<String>) -> Result<Vec<(Pubkey,
                 std::process::exit(exit.status.code().unwrap_or(1));
             }
 
-            // Add program address to the IDL.
-            program.idl.metadata = Some(serde_json::to_value(IdlTestMetadata {
-                address: program_kp.pubkey().to_string(),
-            })?);
-
-            // Persist it.
-            let idl_out = PathBuf::from("target/idl")
-                .join(&program.idl.name)
-                .with_extension("json");
-            write_idl(&program.idl, OutFile::File(idl_out))?;
-
-            programs.push((program_kp.pubkey(), program))
+            programs.push((program_id, program))
         }
 
         println!("Deploy success");
@@ -813,6 +1219,48 @@ fn _deploy(url: Option<String>, keypair: Option<String>) -> Result<Vec<(Pubkey,
     })
 }
 
+// Programs checked out without their keypair, e.g., the examples, can't be
+// deployed to their declared id. When asked to, a new keypair is generated and
+// its address declared in place of the old one.
+fn generate_missing_keypair(program: &Program) -> Result<()> {
+    let keypair_path = program.anchor_keypair_path();
+    if keypair_path.exists() {
+        return Ok(());
+    }
+    let old_id = program.declared_id()?;
+    let program_kp = Keypair::generate(&mut OsRng);
+
+    let lib_rs_path = program.path.join("src/lib.rs");
+    let lib_rs = fs::read_to_string(&lib_rs_path)?;
+    let old_decl = format!("declare_id!(\"{}\")", old_id);
+    if !lib_rs.contains(&old_decl) {
+        return Err(anyhow!(
+            "Unable to find {} in {} to replace with a new program id",
+            old_decl,
+            lib_rs_path.display()
+        ));
+    }
+    fs::create_dir_all("target/deploy")?;
+    let mut keypair_file = File::create(&keypair_path)?;
+    keypair_file.write_all(format!("{:?}", &program_kp.to_bytes()).as_bytes())?;
+    fs::write(
+        &lib_rs_path,
+        lib_rs.replacen(
+            &old_decl,
+            &format!("declare_id!(\"{}\")", program_kp.pubkey()),
+            1,
+        ),
+    )?;
+
+    println!(
+        "Program keypair {} not found, declared the new program id {} in {}",
+        keypair_path.display(),
+        program_kp.pubkey(),
+        lib_rs_path.display()
+    );
+    Ok(())
+}
+
 fn upgrade(program_id: Pubkey, program_filepath: String) -> Result<()> {
     let path: PathBuf = program_filepath.parse().unwrap();
     let program_filepath = path.canonicalize()?.display().to_string();
@@ -840,9 +1288,9 @@ fn upgrade(program_id: Pubkey, program_filepath: String) -> Result<()> {
     })
 }
 
-fn launch(url: Option<String>, keypair: Option<String>) -> Result<()> {
+fn launch(url: Option<String>, keypair: Option<String>, new_program_id: bool) -> Result<()> {
     // Build and deploy.
-    let programs = _deploy(url.clone(), keypair.clone())?;
+    let programs = _deploy(url.clone(), keypair.clone(), new_program_id)?;
 
     with_workspace(|cfg, _path, _cargo| {
         let url = url.unwrap_or_else(|| cfg.cluster.url().to_string());
@@ -873,31 +1321,16 @@ fn launch(url: Option<String>, keypair: Option<String>) -> Result<()> {
 fn with_workspace<R>(f: impl FnOnce(&Config, PathBuf, Option<PathBuf>) -> R) -> R {
     set_workspace_dir_or_exit();
 
-    clear_program_keys().unwrap();
-
     let (cfg, cfg_path, cargo_toml) = Config::discover()
         .expect("Previously set the workspace dir")
         .expect("Anchor.toml must always exist");
     let r = f(&cfg, cfg_path, cargo_toml);
 
     set_workspace_dir_or_exit();
-    clear_program_keys().unwrap();
 
     r
 }
 
-// The Solana CLI doesn't redeploy a program if this file exists.
-// So remove it to make all commands explicit.
-fn clear_program_keys() -> Result<()> {
-    for program in read_all_programs()? {
-        let anchor_keypair_path = program.anchor_keypair_path();
-        if Path::exists(&anchor_keypair_path) {
-            std::fs::remove_file(anchor_keypair_path).expect("Always remove");
-        }
-    }
-    Ok(())
-}
-
 fn create_idl_account(
     cfg: &Config,
     keypair_path: &str,
@@ -1,5 +1,6 @@
 use heck::CamelCase;
 use heck::SnakeCase;
+use solana_sdk::pubkey::Pubkey;
 
 pub fn virtual_manifest() -> String {
     r#"[workspace]
@@ -26,6 +27,7 @@ name = "{1}"
 no-entrypoint = []
 no-idl = []
 cpi = ["no-entrypoint"]
+idl-build = ["anchor-lang/idl-build"]
 default = []
 
 [dependencies]
@@ -87,12 +89,14 @@ features = []"#
         .to_string()
 }
 
-pub fn lib_rs(name: &str) -> String {
+pub fn lib_rs(name: &str, program_id: &Pubkey) -> String {
     format!(
         r#"#![feature(proc_macro_hygiene)]
 
 use anchor_lang::prelude::*;
 
+declare_id!("{}");
+
 #[program]
 pub mod {} {{
     use super::*;
@@ -103,6 +107,7 @@ pub mod {} {{
 
 #[derive(Accounts)]
 pub struct Initialize {{}}"#,
+        program_id,
         name.to_snake_case(),
     )
 }
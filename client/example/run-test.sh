@@ -20,27 +20,38 @@ set -euox pipefail
 
 main() {
     #
-    # Bootup validator.
-    #
-    solana-test-validator > test-validator.log &
-    sleep 5
-
-    #
-    # Deploy programs.
+    # Build programs.
     #
     pushd ../../examples/composite/
-    anchor deploy
+    anchor build
     local composite_pid=$(cat target/idl/composite.json | jq -r .metadata.address)
+    local composite_so=$(pwd)/target/deploy/composite.so
+    popd
+    pushd ../../examples/tutorial/basic-1/
+    anchor build
+    local basic_1_pid=$(cat target/idl/basic_1.json | jq -r .metadata.address)
+    local basic_1_so=$(pwd)/target/deploy/basic_1.so
     popd
     pushd ../../examples/tutorial/basic-2/
-    anchor deploy
+    anchor build
     local basic_2_pid=$(cat target/idl/basic_2.json | jq -r .metadata.address)
+    local basic_2_so=$(pwd)/target/deploy/basic_2.so
     popd
 
+    #
+    # Bootup validator, with the programs at their declared addresses.
+    #
+    solana-test-validator \
+        --bpf-program $composite_pid $composite_so \
+        --bpf-program $basic_1_pid $basic_1_so \
+        --bpf-program $basic_2_pid $basic_2_so \
+        > test-validator.log &
+    sleep 5
+
     #
     # Run Test.
     #
-    cargo run -- --composite-pid $composite_pid --basic-2-pid $basic_2_pid
+    cargo run -- --composite-pid $composite_pid --basic-1-pid $basic_1_pid --basic-2-pid $basic_2_pid
 }
 
 cleanup() {
@@ -17,11 +17,17 @@ use composite::instruction as composite_instruction;
 use composite::{DummyA, DummyB};
 use rand::rngs::OsRng;
 
+// Generates the `basic_1` module from the program's IDL, for programs whose
+// crate isn't available as a dependency.
+anchor_lang::declare_program!("idls/basic_1.json");
+
 #[derive(Clap)]
 pub struct Opts {
     #[clap(long)]
     composite_pid: Pubkey,
     #[clap(long)]
+    basic_1_pid: Pubkey,
+    #[clap(long)]
     basic_2_pid: Pubkey,
 }
 
@@ -40,6 +46,7 @@ fn main() -> Result<()> {
 
     // Run tests.
     composite(&client, opts.composite_pid)?;
+    basic_1(&client, opts.basic_1_pid)?;
     basic_2(&client, opts.basic_2_pid)?;
 
     // Success.
@@ -118,6 +125,54 @@ fn composite(client: &Client, pid: Pubkey) -> Result<()> {
     Ok(())
 }
 
+// Runs a client for examples/tutorial/basic-1, using the client generated by
+// `declare_program!` rather than the program's crate.
+//
+// Make sure to run a localnet with the program deploy to run this example.
+fn basic_1(client: &Client, pid: Pubkey) -> Result<()> {
+    let program = client.program(pid);
+
+    // `Initialize` parameters.
+    let my_account = Keypair::generate(&mut OsRng);
+
+    // Build and send a transaction.
+    program
+        .request()
+        .instruction(system_instruction::create_account(
+            &program.payer(),
+            &my_account.pubkey(),
+            program.rpc().get_minimum_balance_for_rent_exemption(500)?,
+            500,
+            &pid,
+        ))
+        .signer(&my_account)
+        .accounts(basic_1::accounts::Initialize {
+            my_account: my_account.pubkey(),
+            rent: sysvar::rent::ID,
+        })
+        .args(basic_1::instruction::Initialize { data: 1234 })
+        .send()?;
+
+    let account: basic_1::MyAccount = program.account(my_account.pubkey())?;
+    assert_eq!(account.data, 1234);
+
+    // Update the account.
+    program
+        .request()
+        .accounts(basic_1::accounts::Update {
+            my_account: my_account.pubkey(),
+        })
+        .args(basic_1::instruction::Update { data: 4321 })
+        .send()?;
+
+    let account: basic_1::MyAccount = program.account(my_account.pubkey())?;
+    assert_eq!(account.data, 4321);
+
+    println!("Success!");
+
+    Ok(())
+}
+
 // Runs a client for examples/tutorial/basic-2.
 //
 // Make sure to run a localnet with the program deploy to run this example.
@@ -0,0 +1,73 @@
+//! Requests and accounts described by a program's IDL at runtime, for clients
+//! of programs without a crate, or generated client, to build against.
+//!
+//! Arguments and accounts are JSON, encoded as by `anchor_syn::idl::coder`.
+
+use crate::{ClientError, Program};
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::pubkey::Pubkey;
+use anchor_syn::idl::coder;
+use serde_json::Value;
+use solana_sdk::commitment_config::CommitmentConfig;
+use std::collections::HashMap;
+
+pub use anchor_syn::idl::Idl;
+
+/// Builds the instruction, or state method, with the given name, encoding
+/// `args`, an object keyed by argument name, after its sighash.
+///
+/// `accounts` maps the name of every account of the instruction to its
+/// address. Accounts of composite structs are named by their path through
+/// the struct's field, e.g., `inner.vault`.
+pub fn instruction(
+    program_id: Pubkey,
+    idl: &Idl,
+    name: &str,
+    args: &Value,
+    accounts: &HashMap<String, Pubkey>,
+) -> Result<Instruction, ClientError> {
+    let (ix, data) = coder::encode_instruction(idl, name, args).map_err(idl_error)?;
+    let accounts = coder::flatten_accounts(&ix.accounts)
+        .into_iter()
+        .map(|(name, acc)| {
+            let pubkey = *accounts
+                .get(&name)
+                .ok_or_else(|| ClientError::IdlError(format!("Missing account {}", name)))?;
+            Ok(match acc.is_mut {
+                false => AccountMeta::new_readonly(pubkey, acc.is_signer),
+                true => AccountMeta::new(pubkey, acc.is_signer),
+            })
+        })
+        .collect::<Result<_, ClientError>>()?;
+    Ok(Instruction {
+        program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Decodes the data of the account with the given name, checking its
+/// discriminator.
+pub fn decode_account(idl: &Idl, name: &str, data: &[u8]) -> Result<Value, ClientError> {
+    coder::decode_account(idl, name, data).map_err(idl_error)
+}
+
+/// Returns the account with the given name at the given address, decoded
+/// into JSON.
+pub fn account(
+    program: &Program,
+    idl: &Idl,
+    name: &str,
+    address: Pubkey,
+) -> Result<Value, ClientError> {
+    let account = program
+        .rpc()
+        .get_account_with_commitment(&address, CommitmentConfig::recent())?
+        .value
+        .ok_or(ClientError::AccountNotFound)?;
+    decode_account(idl, name, &account.data)
+}
+
+fn idl_error(e: impl std::fmt::Display) -> ClientError {
+    ClientError::IdlError(e.to_string())
+}
@@ -4,15 +4,25 @@
 use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
 use anchor_lang::solana_program::program_error::ProgramError;
 use anchor_lang::solana_program::pubkey::Pubkey;
-use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas};
+use anchor_lang::{AccountDeserialize, Discriminator, Event, InstructionData, ToAccountMetas};
+use solana_account_decoder::UiAccountEncoding;
 use solana_client::client_error::ClientError as SolanaClientError;
 use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+use solana_client::rpc_filter::{Memcmp, MemcmpEncodedBytes, RpcFilterType};
 use solana_sdk::commitment_config::CommitmentConfig;
 use solana_sdk::signature::{Keypair, Signature, Signer};
 use solana_sdk::transaction::Transaction;
 use std::convert::Into;
 use thiserror::Error;
 
+pub mod dynamic;
+#[cfg(feature = "async")]
+pub mod nonblocking;
+
+#[cfg(feature = "async")]
+pub use nonblocking::{AsyncClient, AsyncProgram, AsyncRequestBuilder};
+
 pub use anchor_lang;
 pub use solana_client;
 pub use solana_sdk;
@@ -99,6 +109,25 @@ impl Program {
         T::try_deserialize(&mut data).map_err(Into::into)
     }
 
+    /// Returns all accounts of type `T` owned by the program, along with
+    /// their addresses. Accounts are matched by `T`'s discriminator and any
+    /// additional `filters`, e.g., a `Memcmp` on one of its fields, whose
+    /// offset must account for the 8 byte discriminator.
+    pub fn accounts<T: AccountDeserialize + Discriminator>(
+        &self,
+        filters: Vec<RpcFilterType>,
+    ) -> Result<Vec<(Pubkey, T)>, ClientError> {
+        let config = program_accounts_config::<T>(filters, self.cfg.options.unwrap_or_default());
+        self.rpc()
+            .get_program_accounts_with_config(&self.program_id, config)?
+            .into_iter()
+            .map(|(key, account)| -> Result<(Pubkey, T), ClientError> {
+                let mut data: &[u8] = &account.data;
+                Ok((key, T::try_deserialize(&mut data)?))
+            })
+            .collect()
+    }
+
     pub fn rpc(&self) -> RpcClient {
         RpcClient::new_with_commitment(
             self.cfg.cluster.clone(),
@@ -109,8 +138,72 @@ impl Program {
     pub fn id(&self) -> Pubkey {
         self.program_id
     }
+
+    /// Decodes all events of type `T` emitted by this program, via `emit!`,
+    /// from the given transaction logs. Logs written by other programs, e.g.,
+    /// during CPI, are skipped.
+    pub fn parse_logs<T: Event>(&self, logs: &[String]) -> Result<Vec<T>, ClientError> {
+        parse_logs(&self.program_id, logs)
+    }
 }
 
+// Matches accounts of type `T` by their discriminator, followed by the
+// user's filters.
+fn program_accounts_config<T: Discriminator>(
+    filters: Vec<RpcFilterType>,
+    commitment: CommitmentConfig,
+) -> RpcProgramAccountsConfig {
+    let discriminator = RpcFilterType::Memcmp(Memcmp {
+        offset: 0,
+        bytes: MemcmpEncodedBytes::Binary(bs58::encode(T::DISCRIMINATOR).into_string()),
+        encoding: None,
+    });
+    RpcProgramAccountsConfig {
+        filters: Some(std::iter::once(discriminator).chain(filters).collect()),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            data_slice: None,
+            commitment: Some(commitment),
+        },
+    }
+}
+
+fn parse_logs<T: Event>(program_id: &Pubkey, logs: &[String]) -> Result<Vec<T>, ClientError> {
+    let invoke_prefix = format!("Program {} invoke", program_id);
+    let mut events = vec![];
+    // Stack of programs currently executing, the last of which wrote the
+    // current log.
+    let mut execution: Vec<bool> = vec![];
+    for log in logs {
+        if log.starts_with("Program ") && log.contains(" invoke [") {
+            execution.push(log.starts_with(&invoke_prefix));
+        } else if log.starts_with("Program ")
+            && (log.ends_with(" success") || log.contains(" failed: "))
+        {
+            execution.pop();
+        } else if let Some(data) = log.strip_prefix(PROGRAM_LOG) {
+            if execution.last() != Some(&true) {
+                continue;
+            }
+            let data = match anchor_lang::base64::decode(data) {
+                Ok(data) => data,
+                // Not an event.
+                Err(_) => continue,
+            };
+            if data.len() < 8 || data[..8] != T::DISCRIMINATOR {
+                continue;
+            }
+            let mut event_data: &[u8] = &data[8..];
+            let event = T::deserialize(&mut event_data)
+                .map_err(|e| ClientError::LogParseError(e.to_string()))?;
+            events.push(event);
+        }
+    }
+    Ok(events)
+}
+
+const PROGRAM_LOG: &str = "Program log: ";
+
 #[derive(Debug, Error)]
 pub enum ClientError {
     #[error("Account not found")]
@@ -119,6 +212,10 @@ pub enum ClientError {
     ProgramError(#[from] ProgramError),
     #[error("{0}")]
     SolanaClientError(#[from] SolanaClientError),
+    #[error("Unable to parse log: {0}")]
+    LogParseError(String),
+    #[error("{0}")]
+    IdlError(String),
 }
 
 /// `RequestBuilder` provides a builder interface to create and send
@@ -225,3 +322,84 @@ impl<'a> RequestBuilder<'a> {
             .map_err(Into::into)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::{event, AnchorDeserialize, AnchorSerialize};
+
+    #[event]
+    #[derive(Debug, PartialEq)]
+    struct Transferred {
+        amount: u64,
+    }
+
+    #[event]
+    struct Closed {}
+
+    fn log(event: &impl Event) -> String {
+        format!(
+            "{}{}",
+            PROGRAM_LOG,
+            anchor_lang::base64::encode(event.data())
+        )
+    }
+
+    fn transferred(amount: u64) -> String {
+        log(&Transferred { amount })
+    }
+
+    #[test]
+    fn parse_logs_follows_the_invoke_stack() {
+        let program_id = Pubkey::new_unique();
+        let other_id = Pubkey::new_unique();
+        let logs = vec![
+            transferred(0),
+            format!("Program {} invoke [1]", program_id),
+            transferred(1),
+            log(&Closed {}),
+            "Program log: not an event".to_string(),
+            // Events of the same type logged by another program are skipped.
+            format!("Program {} invoke [2]", other_id),
+            transferred(2),
+            format!("Program {} consumed 1000 of 200000 compute units", other_id),
+            format!("Program {} success", other_id),
+            transferred(3),
+            // As are those of any program it calls into.
+            format!("Program {} invoke [2]", other_id),
+            format!("Program {} invoke [3]", program_id),
+            transferred(4),
+            format!("Program {} success", program_id),
+            transferred(5),
+            format!("Program {} failed: custom program error: 0x1", other_id),
+            transferred(6),
+            format!("Program {} success", program_id),
+            transferred(7),
+        ];
+        assert_eq!(
+            parse_logs::<Transferred>(&program_id, &logs).unwrap(),
+            vec![
+                Transferred { amount: 1 },
+                Transferred { amount: 3 },
+                Transferred { amount: 4 },
+                Transferred { amount: 6 },
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_logs_rejects_malformed_events() {
+        let program_id = Pubkey::new_unique();
+        let mut data = Transferred { amount: 1 }.data();
+        data.truncate(12);
+        let logs = vec![
+            format!("Program {} invoke [1]", program_id),
+            format!("{}{}", PROGRAM_LOG, anchor_lang::base64::encode(data)),
+            format!("Program {} success", program_id),
+        ];
+        assert!(matches!(
+            parse_logs::<Transferred>(&program_id, &logs),
+            Err(ClientError::LogParseError(_))
+        ));
+    }
+}
@@ -0,0 +1,232 @@
+//! Async counterparts of [`Client`](../struct.Client.html),
+//! [`Program`](../struct.Program.html) and
+//! [`RequestBuilder`](../struct.RequestBuilder.html), for use within a tokio
+//! runtime. Enabled by the `async` feature.
+
+use crate::{parse_logs, program_accounts_config, ClientError, Config};
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::pubkey::Pubkey;
+use anchor_lang::{AccountDeserialize, Discriminator, Event, InstructionData, ToAccountMetas};
+use solana_client::rpc_filter::RpcFilterType;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use solana_sdk::transaction::Transaction;
+
+pub mod rpc_client;
+
+use rpc_client::RpcClient;
+
+/// Async counterpart of [`Client`](../struct.Client.html), building
+/// `AsyncProgram` clients via the `program` method.
+pub struct AsyncClient {
+    cfg: Config,
+}
+
+impl AsyncClient {
+    pub fn new(cluster: &str, payer: Keypair) -> Self {
+        Self {
+            cfg: Config {
+                cluster: cluster.to_string(),
+                payer,
+                options: None,
+            },
+        }
+    }
+
+    pub fn new_with_options(cluster: &str, payer: Keypair, options: CommitmentConfig) -> Self {
+        Self {
+            cfg: Config {
+                cluster: cluster.to_string(),
+                payer,
+                options: Some(options),
+            },
+        }
+    }
+
+    pub fn program(&self, program_id: Pubkey) -> AsyncProgram {
+        AsyncProgram {
+            program_id,
+            cfg: Config {
+                cluster: self.cfg.cluster.clone(),
+                options: self.cfg.options,
+                payer: Keypair::from_bytes(&self.cfg.payer.to_bytes()).unwrap(),
+            },
+        }
+    }
+}
+
+/// Async counterpart of [`Program`](../struct.Program.html).
+pub struct AsyncProgram {
+    program_id: Pubkey,
+    cfg: Config,
+}
+
+impl AsyncProgram {
+    pub fn payer(&self) -> Pubkey {
+        self.cfg.payer.pubkey()
+    }
+
+    /// Returns a request builder.
+    pub fn request(&self) -> AsyncRequestBuilder {
+        AsyncRequestBuilder::new(
+            self.program_id,
+            &self.cfg.cluster,
+            Keypair::from_bytes(&self.cfg.payer.to_bytes()).unwrap(),
+            self.cfg.options,
+        )
+    }
+
+    /// Returns the account at the given address.
+    pub async fn account<T: AccountDeserialize>(&self, address: Pubkey) -> Result<T, ClientError> {
+        let account = self
+            .rpc()
+            .get_account_with_commitment(&address, CommitmentConfig::recent())
+            .await?
+            .value
+            .ok_or(ClientError::AccountNotFound)?;
+        let mut data: &[u8] = &account.data;
+        T::try_deserialize(&mut data).map_err(Into::into)
+    }
+
+    /// Returns all accounts of type `T` owned by the program, along with
+    /// their addresses, as with [`Program::accounts`](../struct.Program.html#method.accounts).
+    pub async fn accounts<T: AccountDeserialize + Discriminator>(
+        &self,
+        filters: Vec<RpcFilterType>,
+    ) -> Result<Vec<(Pubkey, T)>, ClientError> {
+        let config = program_accounts_config::<T>(filters, self.cfg.options.unwrap_or_default());
+        self.rpc()
+            .get_program_accounts_with_config(&self.program_id, config)
+            .await?
+            .into_iter()
+            .map(|(key, account)| -> Result<(Pubkey, T), ClientError> {
+                let mut data: &[u8] = &account.data;
+                Ok((key, T::try_deserialize(&mut data)?))
+            })
+            .collect()
+    }
+
+    pub fn rpc(&self) -> RpcClient {
+        RpcClient::new_with_commitment(
+            self.cfg.cluster.clone(),
+            self.cfg.options.unwrap_or_default(),
+        )
+    }
+
+    pub fn id(&self) -> Pubkey {
+        self.program_id
+    }
+
+    /// Decodes all events of type `T` emitted by this program from the given
+    /// transaction logs, as with [`Program::parse_logs`](../struct.Program.html#method.parse_logs).
+    pub fn parse_logs<T: Event>(&self, logs: &[String]) -> Result<Vec<T>, ClientError> {
+        parse_logs(&self.program_id, logs)
+    }
+}
+
+/// Async counterpart of [`RequestBuilder`](../struct.RequestBuilder.html).
+/// Signers must be `Sync`, so that the future returned by `send` can be
+/// spawned onto a multi-threaded runtime.
+pub struct AsyncRequestBuilder<'a> {
+    cluster: String,
+    program_id: Pubkey,
+    accounts: Vec<AccountMeta>,
+    options: CommitmentConfig,
+    instructions: Vec<Instruction>,
+    payer: Keypair,
+    // Serialized instruction data for the target RPC.
+    instruction_data: Option<Vec<u8>>,
+    signers: Vec<&'a (dyn Signer + Sync)>,
+}
+
+impl<'a> AsyncRequestBuilder<'a> {
+    pub fn new(
+        program_id: Pubkey,
+        cluster: &str,
+        payer: Keypair,
+        options: Option<CommitmentConfig>,
+    ) -> Self {
+        Self {
+            program_id,
+            payer,
+            cluster: cluster.to_string(),
+            accounts: Vec::new(),
+            options: options.unwrap_or_default(),
+            instructions: Vec::new(),
+            instruction_data: None,
+            signers: Vec::new(),
+        }
+    }
+
+    pub fn payer(mut self, payer: Keypair) -> Self {
+        self.payer = payer;
+        self
+    }
+
+    pub fn cluster(mut self, url: &str) -> Self {
+        self.cluster = url.to_string();
+        self
+    }
+
+    pub fn instruction(mut self, ix: Instruction) -> Self {
+        self.instructions.push(ix);
+        self
+    }
+
+    pub fn program(mut self, program_id: Pubkey) -> Self {
+        self.program_id = program_id;
+        self
+    }
+
+    pub fn accounts(mut self, accounts: impl ToAccountMetas) -> Self {
+        let mut metas = accounts.to_account_metas(None);
+        self.accounts.append(&mut metas);
+        self
+    }
+
+    pub fn options(mut self, options: CommitmentConfig) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn args(mut self, args: impl InstructionData) -> Self {
+        self.instruction_data = Some(args.data());
+        self
+    }
+
+    pub fn signer(mut self, signer: &'a (dyn Signer + Sync)) -> Self {
+        self.signers.push(signer);
+        self
+    }
+
+    pub async fn send(self) -> Result<Signature, ClientError> {
+        let mut instructions = self.instructions;
+        if let Some(ix_data) = self.instruction_data {
+            instructions.push(Instruction {
+                program_id: self.program_id,
+                data: ix_data,
+                accounts: self.accounts,
+            });
+        }
+
+        let rpc_client = RpcClient::new_with_commitment(self.cluster, self.options);
+
+        let tx = {
+            let (recent_hash, _fee_calc) = rpc_client.get_recent_blockhash().await?;
+            let mut signers: Vec<&dyn Signer> =
+                self.signers.iter().map(|s| *s as &dyn Signer).collect();
+            signers.push(&self.payer);
+            Transaction::new_signed_with_payer(
+                &instructions,
+                Some(&self.payer.pubkey()),
+                &signers,
+                recent_hash,
+            )
+        };
+
+        rpc_client
+            .send_and_confirm_transaction(&tx)
+            .await
+            .map_err(Into::into)
+    }
+}
@@ -0,0 +1,285 @@
+//! A minimal JSON RPC client, implementing the subset of the blocking
+//! `solana_client::rpc_client::RpcClient` used by the async client, over a
+//! non-blocking HTTP client.
+
+use anchor_lang::solana_program::pubkey::Pubkey;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use solana_account_decoder::{UiAccount, UiAccountEncoding};
+use solana_client::client_error::{ClientError, ClientErrorKind, Result as ClientResult};
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+use solana_client::rpc_request::{RpcError, RpcRequest};
+use solana_client::rpc_response::{
+    Response, RpcBlockhashFeeCalculator, RpcKeyedAccount, RpcResult,
+};
+use solana_sdk::account::Account;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::fee_calculator::FeeCalculator;
+use solana_sdk::hash::Hash;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::{Transaction, TransactionError};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+// Interval between signature status requests while confirming a transaction.
+const CONFIRM_POLL_INTERVAL: Duration = Duration::from_millis(500);
+// Interval between rebroadcasts of a transaction that hasn't been processed,
+// in case it was dropped, e.g., by a leader change.
+const REBROADCAST_INTERVAL: Duration = Duration::from_secs(2);
+// Time after which a sent transaction that hasn't been confirmed is given up on.
+const CONFIRM_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Non-blocking counterpart of `solana_client::rpc_client::RpcClient`.
+pub struct RpcClient {
+    url: String,
+    commitment: CommitmentConfig,
+    client: reqwest::Client,
+}
+
+impl RpcClient {
+    pub fn new(url: String) -> Self {
+        Self::new_with_commitment(url, CommitmentConfig::default())
+    }
+
+    pub fn new_with_commitment(url: String, commitment: CommitmentConfig) -> Self {
+        Self {
+            url,
+            commitment,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    pub fn commitment(&self) -> CommitmentConfig {
+        self.commitment
+    }
+
+    pub async fn get_account_with_commitment(
+        &self,
+        pubkey: &Pubkey,
+        commitment: CommitmentConfig,
+    ) -> RpcResult<Option<Account>> {
+        let config = RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            data_slice: None,
+            commitment: Some(commitment),
+        };
+        let response: Response<Option<UiAccount>> = self
+            .send(
+                RpcRequest::GetAccountInfo,
+                json!([pubkey.to_string(), config]),
+            )
+            .await?;
+        Ok(Response {
+            context: response.context,
+            value: response
+                .value
+                .map(|account| decode_account(&account))
+                .transpose()?,
+        })
+    }
+
+    pub async fn get_program_accounts_with_config(
+        &self,
+        program_id: &Pubkey,
+        config: RpcProgramAccountsConfig,
+    ) -> ClientResult<Vec<(Pubkey, Account)>> {
+        let accounts: Vec<RpcKeyedAccount> = self
+            .send(
+                RpcRequest::GetProgramAccounts,
+                json!([program_id.to_string(), config]),
+            )
+            .await?;
+        accounts
+            .into_iter()
+            .map(|keyed| -> ClientResult<(Pubkey, Account)> {
+                let pubkey = Pubkey::from_str(&keyed.pubkey)
+                    .map_err(|_| invalid_response(format!("Invalid pubkey {}", keyed.pubkey)))?;
+                Ok((pubkey, decode_account(&keyed.account)?))
+            })
+            .collect()
+    }
+
+    pub async fn get_recent_blockhash(&self) -> ClientResult<(Hash, FeeCalculator)> {
+        let response: Response<RpcBlockhashFeeCalculator> = self
+            .send(RpcRequest::GetRecentBlockhash, json!([self.commitment]))
+            .await?;
+        let blockhash = Hash::from_str(&response.value.blockhash).map_err(|_| {
+            invalid_response(format!("Invalid blockhash {}", response.value.blockhash))
+        })?;
+        Ok((blockhash, response.value.fee_calculator))
+    }
+
+    /// Returns true if the blockhash can still be used by a transaction, i.e.,
+    /// it hasn't expired.
+    pub async fn is_blockhash_valid(&self, blockhash: &Hash) -> ClientResult<bool> {
+        let response: Response<Option<Value>> = self
+            .send(
+                RpcRequest::GetFeeCalculatorForBlockhash,
+                json!([blockhash.to_string(), self.commitment]),
+            )
+            .await?;
+        Ok(response.value.is_some())
+    }
+
+    pub async fn send_transaction(&self, transaction: &Transaction) -> ClientResult<Signature> {
+        self.send_transaction_with_preflight(transaction, true)
+            .await
+    }
+
+    async fn send_transaction_with_preflight(
+        &self,
+        transaction: &Transaction,
+        preflight: bool,
+    ) -> ClientResult<Signature> {
+        let serialized = bincode::serialize(transaction)
+            .map_err(|e| invalid_request(format!("Unable to serialize transaction: {}", e)))?;
+        let signature: String = self
+            .send(
+                RpcRequest::SendTransaction,
+                json!([
+                    anchor_lang::base64::encode(serialized),
+                    {
+                        "encoding": "base64",
+                        "skipPreflight": !preflight,
+                        "preflightCommitment": self.commitment.commitment,
+                    },
+                ]),
+            )
+            .await?;
+        Signature::from_str(&signature)
+            .map_err(|_| invalid_response(format!("Invalid signature {}", signature)))
+    }
+
+    /// Sends the transaction, then polls its status until it's confirmed at
+    /// the client's commitment, returning any error it failed with. Until it's
+    /// processed, the transaction is rebroadcast, failing once its blockhash
+    /// has expired, since it can then never be processed.
+    pub async fn send_and_confirm_transaction(
+        &self,
+        transaction: &Transaction,
+    ) -> ClientResult<Signature> {
+        let signature = self.send_transaction(transaction).await?;
+        let start = Instant::now();
+        let mut last_broadcast = start;
+        while start.elapsed() < CONFIRM_TIMEOUT {
+            let statuses: Response<Vec<Option<SignatureStatus>>> = self
+                .send(
+                    RpcRequest::GetSignatureStatuses,
+                    json!([[signature.to_string()]]),
+                )
+                .await?;
+            if let Some(Some(status)) = statuses.value.into_iter().next() {
+                if let Some(err) = status.err {
+                    return Err(ClientErrorKind::TransactionError(err).into());
+                }
+                if status.confirmation() >= Confirmation::required(self.commitment) {
+                    return Ok(signature);
+                }
+            } else if last_broadcast.elapsed() >= REBROADCAST_INTERVAL {
+                if !self
+                    .is_blockhash_valid(&transaction.message.recent_blockhash)
+                    .await?
+                {
+                    return Err(RpcError::ForUser(format!(
+                        "Blockhash of transaction {} expired before it was processed",
+                        signature
+                    ))
+                    .into());
+                }
+                // The transaction was already simulated when first sent.
+                self.send_transaction_with_preflight(transaction, false)
+                    .await?;
+                last_broadcast = Instant::now();
+            }
+            tokio::time::sleep(CONFIRM_POLL_INTERVAL).await;
+        }
+        Err(RpcError::ForUser(format!(
+            "Unable to confirm transaction {} within {:?}",
+            signature, CONFIRM_TIMEOUT
+        ))
+        .into())
+    }
+
+    /// Sends a JSON RPC request, returning its deserialized result.
+    pub async fn send<T: DeserializeOwned>(
+        &self,
+        request: RpcRequest,
+        params: Value,
+    ) -> ClientResult<T> {
+        let response: Value = self
+            .client
+            .post(&self.url)
+            .json(&request.build_request_json(1, params))
+            .send()
+            .await
+            .map_err(|e| invalid_request(e.to_string()))?
+            .json()
+            .await
+            .map_err(|e| invalid_response(e.to_string()))?;
+        if let Some(err) = response.get("error") {
+            return Err(invalid_request(format!("{} failed: {}", request, err)));
+        }
+        Ok(serde_json::from_value(response["result"].clone())?)
+    }
+}
+
+// Subset of the status returned by `getSignatureStatuses`.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SignatureStatus {
+    confirmations: Option<usize>,
+    err: Option<TransactionError>,
+    // Only returned by nodes that track it.
+    confirmation_status: Option<String>,
+}
+
+impl SignatureStatus {
+    fn confirmation(&self) -> Confirmation {
+        match self.confirmation_status.as_deref() {
+            Some("finalized") => Confirmation::Finalized,
+            Some("confirmed") => Confirmation::Confirmed,
+            Some(_) => Confirmation::Processed,
+            // Rooted transactions no longer count confirmations.
+            None if self.confirmations.is_none() => Confirmation::Finalized,
+            None => Confirmation::Processed,
+        }
+    }
+}
+
+// Stages a transaction's status goes through, in order.
+#[derive(PartialEq, PartialOrd)]
+enum Confirmation {
+    Processed,
+    Confirmed,
+    Finalized,
+}
+
+impl Confirmation {
+    // The stage at which a transaction satisfies the commitment.
+    fn required(commitment: CommitmentConfig) -> Self {
+        if commitment == CommitmentConfig::max() || commitment == CommitmentConfig::root() {
+            Confirmation::Finalized
+        } else if commitment == CommitmentConfig::single()
+            || commitment == CommitmentConfig::single_gossip()
+        {
+            Confirmation::Confirmed
+        } else {
+            Confirmation::Processed
+        }
+    }
+}
+
+fn decode_account(account: &UiAccount) -> ClientResult<Account> {
+    account
+        .decode()
+        .ok_or_else(|| invalid_response("Unable to decode account data".to_string()))
+}
+
+fn invalid_request(message: String) -> ClientError {
+    RpcError::RpcRequestError(message).into()
+}
+
+fn invalid_response(message: String) -> ClientError {
+    RpcError::ParseError(message).into()
+}
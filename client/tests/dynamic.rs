@@ -0,0 +1,187 @@
+//! Checks that instructions built from a program's IDL at runtime match those
+//! built with the types generated by `#[program]` and `#[derive(Accounts)]`.
+
+use anchor_client::dynamic;
+use anchor_lang::prelude::*;
+use anchor_lang::{InstructionData, ToAccountMetas};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod dynamic_test {
+    use super::*;
+
+    #[allow(unused_variables)]
+    pub fn set_data(
+        _ctx: Context<SetData>,
+        amount: u64,
+        authority: Pubkey,
+        name: String,
+        bytes: Vec<u8>,
+        limit: Option<u16>,
+        config: Config,
+    ) -> ProgramResult {
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct SetData<'info> {
+    #[account(mut)]
+    pub data: AccountInfo<'info>,
+    #[account(signer)]
+    pub authority: AccountInfo<'info>,
+    pub nested: Nested<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Nested<'info> {
+    #[account(mut)]
+    pub vault: AccountInfo<'info>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct Config {
+    pub enabled: bool,
+    pub mode: Mode,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub enum Mode {
+    Off,
+    Limit { max: u32 },
+}
+
+// The IDL of the program above, as written by `anchor build`.
+fn idl() -> dynamic::Idl {
+    serde_json::from_value(json!({
+        "version": "0.1.0",
+        "name": "dynamic_test",
+        "instructions": [{
+            "name": "setData",
+            "accounts": [
+                { "name": "data", "isMut": true, "isSigner": false },
+                { "name": "authority", "isMut": false, "isSigner": true },
+                {
+                    "name": "nested",
+                    "accounts": [
+                        { "name": "vault", "isMut": true, "isSigner": false },
+                        { "name": "rent", "isMut": false, "isSigner": false },
+                    ],
+                },
+            ],
+            "args": [
+                { "name": "amount", "type": "u64" },
+                { "name": "authority", "type": "publicKey" },
+                { "name": "name", "type": "string" },
+                { "name": "bytes", "type": "bytes" },
+                { "name": "limit", "type": { "option": "u16" } },
+                { "name": "config", "type": { "defined": "Config" } },
+            ],
+        }],
+        "types": [
+            {
+                "name": "Config",
+                "type": {
+                    "kind": "struct",
+                    "fields": [
+                        { "name": "enabled", "type": "bool" },
+                        { "name": "mode", "type": { "defined": "Mode" } },
+                    ],
+                },
+            },
+            {
+                "name": "Mode",
+                "type": {
+                    "kind": "enum",
+                    "variants": [
+                        { "name": "Off" },
+                        { "name": "Limit", "fields": [{ "name": "max", "type": "u32" }] },
+                    ],
+                },
+            },
+        ],
+    }))
+    .unwrap()
+}
+
+fn args_json(authority: Pubkey, limit: Option<u16>, mode_json: Value) -> Value {
+    json!({
+        "amount": u64::MAX,
+        "authority": authority.to_string(),
+        "name": "dynamic",
+        "bytes": [1, 2, 3],
+        "limit": limit,
+        "config": { "enabled": true, "mode": mode_json },
+    })
+}
+
+fn check_set_data(limit: Option<u16>, mode: Mode, mode_json: Value) {
+    let authority = Pubkey::new_unique();
+    let ix_accounts = accounts::SetData {
+        data: Pubkey::new_unique(),
+        authority,
+        nested: accounts::Nested {
+            vault: Pubkey::new_unique(),
+            rent: anchor_lang::solana_program::sysvar::rent::ID,
+        },
+    };
+    let args = instruction::SetData {
+        amount: u64::MAX,
+        authority,
+        name: "dynamic".to_string(),
+        bytes: vec![1, 2, 3],
+        limit,
+        config: Config {
+            enabled: true,
+            mode,
+        },
+    };
+
+    let ix = dynamic::instruction(
+        ID,
+        &idl(),
+        "set_data",
+        &args_json(authority, limit, mode_json),
+        &[
+            ("data", ix_accounts.data),
+            ("authority", ix_accounts.authority),
+            ("nested.vault", ix_accounts.nested.vault),
+            ("nested.rent", ix_accounts.nested.rent),
+        ]
+        .iter()
+        .map(|(name, pubkey)| (name.to_string(), *pubkey))
+        .collect(),
+    )
+    .unwrap();
+
+    assert_eq!(ix.program_id, ID);
+    assert_eq!(ix.data, args.data());
+    assert_eq!(ix.accounts, ix_accounts.to_account_metas(None));
+}
+
+#[test]
+fn instruction_matches_generated_types() {
+    check_set_data(None, Mode::Off, json!("Off"));
+    check_set_data(
+        Some(7),
+        Mode::Limit { max: 42 },
+        json!({ "Limit": { "max": 42 } }),
+    );
+}
+
+#[test]
+fn instruction_requires_every_account() {
+    let err = dynamic::instruction(
+        ID,
+        &idl(),
+        "set_data",
+        &args_json(Pubkey::new_unique(), None, json!("Off")),
+        &HashMap::new(),
+    )
+    .unwrap_err();
+    assert_eq!(err.to_string(), "Missing account data");
+}
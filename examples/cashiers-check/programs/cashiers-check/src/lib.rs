@@ -9,6 +9,8 @@ use anchor_lang::prelude::*;
 use anchor_spl::token::{self, TokenAccount, Transfer};
 use std::convert::Into;
 
+declare_id!("BJxjUauRzQnEiq9FAUWH4nyWLSwQNPEsWgBe8HZYPipc");
+
 #[program]
 pub mod cashiers_check {
     use super::*;
@@ -77,7 +79,7 @@ pub mod cashiers_check {
         let cpi_program = ctx.accounts.token_program.clone();
         let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
         token::transfer(cpi_ctx, ctx.accounts.check.amount)?;
-        ctx.accounts.check.burned = true;
+        // The check is closed on exit, returning its rent to the owner.
         Ok(())
     }
 }
@@ -138,7 +140,7 @@ pub struct CashCheck<'info> {
 
 #[derive(Accounts)]
 pub struct CancelCheck<'info> {
-    #[account(mut, has_one = vault, has_one = from)]
+    #[account(mut, has_one = vault, has_one = from, close = owner)]
     check: ProgramAccount<'info, Check>,
     #[account(mut)]
     vault: AccountInfo<'info>,
@@ -149,7 +151,7 @@ pub struct CancelCheck<'info> {
     check_signer: AccountInfo<'info>,
     #[account(mut, has_one = owner)]
     from: CpiAccount<'info, TokenAccount>,
-    #[account(signer)]
+    #[account(mut, signer)]
     owner: AccountInfo<'info>,
     token_program: AccountInfo<'info>,
 }
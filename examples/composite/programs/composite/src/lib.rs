@@ -5,6 +5,8 @@
 
 use anchor_lang::prelude::*;
 
+declare_id!("2GBFLWpTjvpM6QhPQ6DStnAy68S37PAb4bqubLnGD7oE");
+
 #[program]
 mod composite {
     use super::*;
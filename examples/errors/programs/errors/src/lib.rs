@@ -5,6 +5,8 @@
 
 use anchor_lang::prelude::*;
 
+declare_id!("Hys88qbw6pfE4MM6k6ECMUN52bLyWCGB8Rze8xsBGz84");
+
 #[program]
 mod errors {
     use super::*;
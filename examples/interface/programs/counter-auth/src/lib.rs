@@ -8,6 +8,8 @@
 use anchor_lang::prelude::*;
 use counter::Auth;
 
+declare_id!("6tmZ2X3JSC5qfx4tYAU9X1iatCkbYszzzfczPfLRGNv4");
+
 #[program]
 pub mod counter_auth {
     use super::*;
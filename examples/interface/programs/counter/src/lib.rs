@@ -10,6 +10,8 @@
 
 use anchor_lang::prelude::*;
 
+declare_id!("79F2MAsvSQmhuTTzm14PdGHmHok48KhqHdEk51mTBp18");
+
 #[program]
 pub mod counter {
     use super::*;
@@ -53,7 +55,7 @@ pub struct SetCount<'info> {
 impl<'info> SetCount<'info> {
     // Auxiliary account validation requiring program inputs. As a convention,
     // we separate it from the business logic of the instruction handler itself.
-    pub fn accounts(counter: &Counter, ctx: &Context<SetCount>) -> Result<()> {
+    pub fn accounts(counter: &counter::Counter, ctx: &Context<SetCount>) -> Result<()> {
         if ctx.accounts.auth_program.key != &counter.auth_program {
             return Err(ErrorCode::InvalidAuthProgram.into());
         }
@@ -8,6 +8,8 @@ use anchor_lang::solana_program::instruction::Instruction;
 use anchor_lang::solana_program::program;
 use anchor_spl::token::{self, TokenAccount, Transfer};
 
+declare_id!("GtA9RSj6Dbe5G23N5EH97KCJMLjSQ2yi6iJ4zUoE2kqE");
+
 mod calculator;
 
 #[program]
@@ -275,7 +277,7 @@ pub struct WhitelistDeposit<'info> {
 
 #[derive(Accounts)]
 pub struct WhitelistTransfer<'info> {
-    lockup: ProgramState<'info, Lockup>,
+    lockup: ProgramState<'info, lockup::Lockup>,
     #[account(signer)]
     beneficiary: AccountInfo<'info>,
     whitelisted_program: AccountInfo<'info>,
@@ -482,7 +484,7 @@ pub fn is_whitelisted<'info>(transfer: &WhitelistTransfer<'info>) -> Result<()>
     Ok(())
 }
 
-fn whitelist_auth(lockup: &Lockup, ctx: &Context<Auth>) -> Result<()> {
+fn whitelist_auth(lockup: &lockup::Lockup, ctx: &Context<Auth>) -> Result<()> {
     if &lockup.authority != ctx.accounts.authority.key {
         return Err(ErrorCode::Unauthorized.into());
     }
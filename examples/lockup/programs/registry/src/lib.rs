@@ -9,6 +9,8 @@ use anchor_spl::token::{self, Mint, TokenAccount, Transfer};
 use lockup::{CreateVesting, RealizeLock, Realizor, Vesting};
 use std::convert::Into;
 
+declare_id!("Fz8SBTYZeCQQPLDECqdf8LX3rgQUxKxLqxuP7zqJKtP1");
+
 #[program]
 mod registry {
     use super::*;
@@ -736,7 +738,7 @@ pub struct DepositLocked<'info> {
     member_signer: AccountInfo<'info>,
 
     // Program specific.
-    registry: ProgramState<'info, Registry>,
+    registry: ProgramState<'info, registry::Registry>,
     registrar: ProgramAccount<'info, Registrar>,
     #[account(belongs_to = registrar, has_one = beneficiary)]
     member: ProgramAccount<'info, Member>,
@@ -906,7 +908,7 @@ pub struct WithdrawLocked<'info> {
     member_signer: AccountInfo<'info>,
 
     // Program specific.
-    registry: ProgramState<'info, Registry>,
+    registry: ProgramState<'info, registry::Registry>,
     registrar: ProgramAccount<'info, Registrar>,
     #[account(belongs_to = registrar, has_one = beneficiary)]
     member: ProgramAccount<'info, Member>,
@@ -969,7 +971,7 @@ pub struct ClaimReward<'info> {
 #[derive(Accounts)]
 pub struct ClaimRewardLocked<'info> {
     cmn: ClaimRewardCommon<'info>,
-    registry: ProgramState<'info, Registry>,
+    registry: ProgramState<'info, registry::Registry>,
     #[account("lockup_program.key == &registry.lockup_program")]
     lockup_program: AccountInfo<'info>,
 }
@@ -5,6 +5,8 @@
 
 use anchor_lang::prelude::*;
 
+declare_id!("CuC8AVJ5sjqDsXt1iG4skz46m2PhziFUuGo3JzXAmYES");
+
 #[program]
 pub mod misc {
     use super::*;
@@ -13,6 +15,50 @@ pub mod misc {
         ctx.accounts.data.idata = idata;
         Ok(())
     }
+
+    pub fn initialize_with_payer(
+        ctx: Context<InitializeWithPayer>,
+        udata: u128,
+        idata: i128,
+    ) -> ProgramResult {
+        ctx.accounts.data.udata = udata;
+        ctx.accounts.data.idata = idata;
+        Ok(())
+    }
+
+    pub fn initialize_pda(ctx: Context<InitializePda>) -> ProgramResult {
+        ctx.accounts.pda.bump = ctx.bumps["pda"];
+        Ok(())
+    }
+
+    pub fn test_close(_ctx: Context<TestClose>) -> ProgramResult {
+        Ok(())
+    }
+
+    pub fn initialize_zero_copy(
+        ctx: Context<InitializeZeroCopy>,
+        udata: u64,
+        idata: i64,
+    ) -> ProgramResult {
+        let mut data = ctx.accounts.data.load_init()?;
+        data.udata = udata;
+        data.idata = idata;
+        Ok(())
+    }
+
+    pub fn update_zero_copy(ctx: Context<UpdateZeroCopy>, udata: u64) -> ProgramResult {
+        let mut data = ctx.accounts.data.load_mut()?;
+        data.udata = udata;
+        Ok(())
+    }
+
+    pub fn test_event(_ctx: Context<TestEvent>, data: u64) -> ProgramResult {
+        emit!(MyEvent {
+            data,
+            label: "hello".to_string(),
+        });
+        Ok(())
+    }
 }
 
 #[derive(Accounts)]
@@ -22,8 +68,75 @@ pub struct Initialize<'info> {
     rent: Sysvar<'info, Rent>,
 }
 
+#[derive(Accounts)]
+pub struct InitializeWithPayer<'info> {
+    #[account(init, payer = payer, space = 8 + 16 + 16)]
+    data: ProgramAccount<'info, Data>,
+    #[account(mut, signer)]
+    payer: AccountInfo<'info>,
+    rent: Sysvar<'info, Rent>,
+    system_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializePda<'info> {
+    #[account(
+        init,
+        seeds = [b"pda".as_ref(), payer.key.as_ref()],
+        bump,
+        payer = payer,
+        space = 8 + 1,
+    )]
+    pda: ProgramAccount<'info, PdaAccount>,
+    #[account(mut, signer)]
+    payer: AccountInfo<'info>,
+    rent: Sysvar<'info, Rent>,
+    system_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct TestClose<'info> {
+    #[account(mut, close = sol_dest)]
+    data: ProgramAccount<'info, Data>,
+    #[account(mut)]
+    sol_dest: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeZeroCopy<'info> {
+    #[account(init)]
+    data: ZeroCopyAccount<'info, ZeroCopyData>,
+    rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateZeroCopy<'info> {
+    #[account(mut)]
+    data: ZeroCopyAccount<'info, ZeroCopyData>,
+}
+
+#[derive(Accounts)]
+pub struct TestEvent {}
+
 #[account]
 pub struct Data {
     udata: u128,
     idata: i128,
 }
+
+#[account]
+pub struct PdaAccount {
+    bump: u8,
+}
+
+#[account(zero_copy)]
+pub struct ZeroCopyData {
+    udata: u64,
+    idata: i64,
+}
+
+#[event]
+pub struct MyEvent {
+    pub data: u64,
+    pub label: String,
+}
@@ -24,4 +24,110 @@ describe("misc", () => {
     assert.ok(dataAccount.udata.eq(new anchor.BN(1234)));
     assert.ok(dataAccount.idata.eq(new anchor.BN(22)));
   });
+
+  it("Can create an account paid for by the program", async () => {
+    const data = new anchor.web3.Account();
+    const program = anchor.workspace.Misc;
+    await program.rpc.initializeWithPayer(
+      new anchor.BN(1234),
+      new anchor.BN(22),
+      {
+        accounts: {
+          data: data.publicKey,
+          payer: program.provider.wallet.publicKey,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        },
+        signers: [data],
+      }
+    );
+    const dataAccount = await program.account.data(data.publicKey);
+    assert.ok(dataAccount.udata.eq(new anchor.BN(1234)));
+    assert.ok(dataAccount.idata.eq(new anchor.BN(22)));
+  });
+
+  it("Can create a program derived address with its bump seed", async () => {
+    const program = anchor.workspace.Misc;
+    const payer = program.provider.wallet.publicKey;
+    const [pda, bump] = await anchor.web3.PublicKey.findProgramAddress(
+      [Buffer.from("pda"), payer.toBuffer()],
+      program.programId
+    );
+    await program.rpc.initializePda({
+      accounts: {
+        pda,
+        payer,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      },
+    });
+    const pdaAccount = await program.account.pdaAccount(pda);
+    assert.ok(pdaAccount.bump === bump);
+  });
+
+  it("Can close an account", async () => {
+    const data = new anchor.web3.Account();
+    const program = anchor.workspace.Misc;
+    await program.rpc.initialize(new anchor.BN(1234), new anchor.BN(22), {
+      accounts: {
+        data: data.publicKey,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      },
+      signers: [data],
+      instructions: [await program.account.data.createInstruction(data)],
+    });
+    const solDest = new anchor.web3.Account().publicKey;
+    const rentLamports = await program.provider.connection.getBalance(
+      data.publicKey
+    );
+    await program.rpc.testClose({
+      accounts: {
+        data: data.publicKey,
+        solDest,
+      },
+    });
+    const closedAccount = await program.provider.connection.getAccountInfo(
+      data.publicKey
+    );
+    assert.ok(closedAccount === null);
+    assert.ok(
+      (await program.provider.connection.getBalance(solDest)) === rentLamports
+    );
+  });
+
+  it("Can use a zero copy account", async () => {
+    const data = new anchor.web3.Account();
+    const program = anchor.workspace.Misc;
+    await program.rpc.initializeZeroCopy(
+      new anchor.BN(1234),
+      new anchor.BN(-22),
+      {
+        accounts: {
+          data: data.publicKey,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        },
+        signers: [data],
+        instructions: [
+          await program.account.zeroCopyData.createInstruction(data),
+        ],
+      }
+    );
+    let dataAccount = await program.account.zeroCopyData(data.publicKey);
+    assert.ok(dataAccount.udata.eq(new anchor.BN(1234)));
+    assert.ok(dataAccount.idata.eq(new anchor.BN(-22)));
+
+    await program.rpc.updateZeroCopy(new anchor.BN(99), {
+      accounts: {
+        data: data.publicKey,
+      },
+    });
+    dataAccount = await program.account.zeroCopyData(data.publicKey);
+    assert.ok(dataAccount.udata.eq(new anchor.BN(99)));
+  });
+
+  it("Can emit an event", async () => {
+    const program = anchor.workspace.Misc;
+    await program.rpc.testEvent(new anchor.BN(5));
+    assert.ok(program.idl.events[0].name === "MyEvent");
+  });
 });
@@ -7,6 +7,8 @@ use anchor_lang::solana_program;
 use anchor_lang::solana_program::instruction::Instruction;
 use std::convert::Into;
 
+declare_id!("B9W79Nsorqq1aGqGKjtLv8VLamDjBttZQZuyCLg42ezb");
+
 #[program]
 pub mod multisig {
     use super::*;
@@ -3,7 +3,9 @@
 #![feature(proc_macro_hygiene)]
 
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Burn, MintTo, Transfer};
+use anchor_spl::token::{self, Burn, MintTo, Token, Transfer};
+
+declare_id!("5iVwUfs54oWp1Z9LvoKP3wJoGJbv6LPYMPgXj97B25sx");
 
 #[program]
 mod token_proxy {
@@ -24,35 +26,32 @@ mod token_proxy {
 
 #[derive(Accounts)]
 pub struct ProxyTransfer<'info> {
-    #[account(signer)]
-    pub authority: AccountInfo<'info>,
+    pub authority: Signer<'info>,
     #[account(mut)]
     pub from: AccountInfo<'info>,
     #[account(mut)]
     pub to: AccountInfo<'info>,
-    pub token_program: AccountInfo<'info>,
+    pub token_program: Program<'info, Token>,
 }
 
 #[derive(Accounts)]
 pub struct ProxyMintTo<'info> {
-    #[account(signer)]
-    pub authority: AccountInfo<'info>,
+    pub authority: Signer<'info>,
     #[account(mut)]
     pub mint: AccountInfo<'info>,
     #[account(mut)]
     pub to: AccountInfo<'info>,
-    pub token_program: AccountInfo<'info>,
+    pub token_program: Program<'info, Token>,
 }
 
 #[derive(Accounts)]
 pub struct ProxyBurn<'info> {
-    #[account(signer)]
-    pub authority: AccountInfo<'info>,
+    pub authority: Signer<'info>,
     #[account(mut)]
     pub mint: AccountInfo<'info>,
     #[account(mut)]
     pub to: AccountInfo<'info>,
-    pub token_program: AccountInfo<'info>,
+    pub token_program: Program<'info, Token>,
 }
 
 impl<'a, 'b, 'c, 'info> From<&mut ProxyTransfer<'info>>
@@ -62,9 +61,9 @@ impl<'a, 'b, 'c, 'info> From<&mut ProxyTransfer<'info>>
         let cpi_accounts = Transfer {
             from: accounts.from.clone(),
             to: accounts.to.clone(),
-            authority: accounts.authority.clone(),
+            authority: accounts.authority.to_account_info(),
         };
-        let cpi_program = accounts.token_program.clone();
+        let cpi_program = accounts.token_program.to_account_info();
         CpiContext::new(cpi_program, cpi_accounts)
     }
 }
@@ -76,9 +75,9 @@ impl<'a, 'b, 'c, 'info> From<&mut ProxyMintTo<'info>>
         let cpi_accounts = MintTo {
             mint: accounts.mint.clone(),
             to: accounts.to.clone(),
-            authority: accounts.authority.clone(),
+            authority: accounts.authority.to_account_info(),
         };
-        let cpi_program = accounts.token_program.clone();
+        let cpi_program = accounts.token_program.to_account_info();
         CpiContext::new(cpi_program, cpi_accounts)
     }
 }
@@ -88,9 +87,9 @@ impl<'a, 'b, 'c, 'info> From<&mut ProxyBurn<'info>> for CpiContext<'a, 'b, 'c, '
         let cpi_accounts = Burn {
             mint: accounts.mint.clone(),
             to: accounts.to.clone(),
-            authority: accounts.authority.clone(),
+            authority: accounts.authority.to_account_info(),
         };
-        let cpi_program = accounts.token_program.clone();
+        let cpi_program = accounts.token_program.to_account_info();
         CpiContext::new(cpi_program, cpi_accounts)
     }
 }
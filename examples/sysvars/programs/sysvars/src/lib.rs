@@ -2,6 +2,8 @@
 
 use anchor_lang::prelude::*;
 
+declare_id!("9WtN2QXHmimDS74ELoEae2qgkkWfaaTL9Vnd9Vri9vRN");
+
 #[program]
 mod sysvars {
     use super::*;
@@ -2,6 +2,8 @@
 
 use anchor_lang::prelude::*;
 
+declare_id!("21CTGdNGLLEEy8pdiWY4NzoFveWop98WxPF5vNyueQgv");
+
 #[program]
 mod basic_0 {
     use super::*;
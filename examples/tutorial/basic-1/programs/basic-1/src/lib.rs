@@ -2,6 +2,8 @@
 
 use anchor_lang::prelude::*;
 
+declare_id!("AFGD7JGHP9vQ1NU9AUZiqEC5qjcTw8qPM4ihL1fsD4Y1");
+
 #[program]
 mod basic_1 {
     use super::*;
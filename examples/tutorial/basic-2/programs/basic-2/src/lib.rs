@@ -2,6 +2,8 @@
 
 use anchor_lang::prelude::*;
 
+declare_id!("Hdn88ePYb71TAuuHDjJsJ6pTUn8tmrFs81t7CC1Znmun");
+
 // Define the program's instruction handlers.
 
 #[program]
@@ -4,6 +4,8 @@
 use anchor_lang::prelude::*;
 use puppet::{Puppet, SetData};
 
+declare_id!("HYMTa1ezuRabP5nVU1GRSwFTi1XtRbKMnHqio7qLPF94");
+
 #[program]
 mod puppet_master {
     use super::*;
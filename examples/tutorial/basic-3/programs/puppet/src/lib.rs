@@ -2,6 +2,8 @@
 
 use anchor_lang::prelude::*;
 
+declare_id!("AAb4SxPuCvLmMr17QpWHTqVSPuT21W9ZtKbPAyEhHr1K");
+
 #[program]
 mod puppet {
     use super::*;
@@ -29,7 +31,7 @@ pub struct SetData<'info> {
     pub puppet: ProgramAccount<'info, Puppet>,
 }
 
-#[account]
+#[account(owner = ID)]
 pub struct Puppet {
     pub data: u64,
 }
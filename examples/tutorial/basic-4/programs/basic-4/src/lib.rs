@@ -3,6 +3,8 @@
 // #region code
 use anchor_lang::prelude::*;
 
+declare_id!("C1GaZVWYamUBBQGsX18yR8eP2xjM9QmkyFJrep4P4Aya");
+
 #[program]
 pub mod basic_4 {
     use super::*;
@@ -17,16 +17,53 @@ use syn::parse_macro_input;
 /// As a result, any calls to `AccountDeserialize`'s `try_deserialize` will
 /// check this discriminator. If it doesn't match, an invalid account was given,
 /// and the account deserialization will exit with an error.
+///
+/// # Zero Copy
+///
+/// For accounts too large to deserialize, use `#[account(zero_copy)]`. Instead
+/// of the serialization traits, the struct is made `#[repr(C)]`, implements
+/// [`ZeroCopy`](./trait.ZeroCopy.html), and is accessed through a
+/// [`ZeroCopyAccount`](./struct.ZeroCopyAccount.html) casting the account data
+/// in place. All fields must be `Pod` types laid out without padding.
+///
+/// # Owner
+///
+/// Accounts are owned by the program defining them, i.e., the crate's `ID`
+/// given by `declare_id!`, unless another owner is specified with
+/// `#[account(owner = <expr>)]`, where `expr` evaluates to the program's
+/// `Pubkey`. Other programs reading the account through a
+/// [`CpiAccount`](./struct.CpiAccount.html) reject any account not owned by
+/// that program, regardless of the account's namespace. Accounts whose owner
+/// isn't known at compile time, e.g., the framework's own IDL account, which
+/// is owned by whichever program created it, must opt out of the check with
+/// `#[account(owner = none)]`, and should be checked with a constraint instead.
 #[proc_macro_attribute]
 pub fn account(
     args: proc_macro::TokenStream,
     input: proc_macro::TokenStream,
 ) -> proc_macro::TokenStream {
-    let namespace = args.to_string().replace("\"", "");
+    let args = parse_macro_input!(args as AccountArgs);
+    let is_zero_copy = args.zero_copy;
+    let namespace = args.namespace.unwrap_or_default();
 
     let account_strct = parse_macro_input!(input as syn::ItemStruct);
     let account_name = &account_strct.ident;
 
+    let owner_impl = {
+        let owner = match &args.owner {
+            None => quote! { Some(crate::ID) },
+            Some(AccountOwner::None) => quote! { None },
+            Some(AccountOwner::Expr(owner)) => quote! { Some(#owner) },
+        };
+        quote! {
+            impl anchor_lang::Owner for #account_name {
+                fn owner() -> Option<anchor_lang::solana_program::pubkey::Pubkey> {
+                    #owner
+                }
+            }
+        }
+    };
+
     let discriminator: proc_macro2::TokenStream = {
         // Namespace the discriminator to prevent collisions.
         let discriminator_preimage = {
@@ -43,6 +80,22 @@ pub fn account(
         format!("{:?}", discriminator).parse().unwrap()
     };
 
+    #[cfg(feature = "idl-build")]
+    let idl_build = anchor_syn::codegen::idl_build::generate_account(&account_strct, is_zero_copy);
+    #[cfg(not(feature = "idl-build"))]
+    let idl_build = quote! {};
+
+    if is_zero_copy {
+        let zero_copy = generate_zero_copy(&account_strct, discriminator);
+        return proc_macro::TokenStream::from(quote! {
+            #zero_copy
+
+            #owner_impl
+
+            #idl_build
+        });
+    }
+
     let coder = quote! {
         impl anchor_lang::AccountSerialize for #account_name {
             fn try_serialize<W: std::io::Write>(&self, writer: &mut W) -> std::result::Result<(), ProgramError> {
@@ -75,6 +128,10 @@ pub fn account(
                     .map_err(|_| ProgramError::InvalidAccountData)
             }
         }
+
+        impl anchor_lang::Discriminator for #account_name {
+            const DISCRIMINATOR: [u8; 8] = #discriminator;
+        }
     };
 
     proc_macro::TokenStream::from(quote! {
@@ -82,5 +139,141 @@ pub fn account(
         #account_strct
 
         #coder
+
+        #owner_impl
+
+        #idl_build
+    })
+}
+
+// Arguments to the `account` attribute: an optional namespace string literal,
+// `zero_copy`, and `owner = <expr>` or `owner = none`, separated by commas.
+#[derive(Default)]
+struct AccountArgs {
+    namespace: Option<String>,
+    zero_copy: bool,
+    owner: Option<AccountOwner>,
+}
+
+enum AccountOwner {
+    // `owner = none`, skipping the owner check.
+    None,
+    Expr(syn::Expr),
+}
+
+impl syn::parse::Parse for AccountArgs {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let mut args = AccountArgs::default();
+        while !input.is_empty() {
+            if input.peek(syn::LitStr) {
+                let namespace: syn::LitStr = input.parse()?;
+                args.namespace = Some(namespace.value());
+            } else {
+                let ident: syn::Ident = input.parse()?;
+                match ident.to_string().as_str() {
+                    "zero_copy" => args.zero_copy = true,
+                    "owner" => {
+                        input.parse::<syn::Token![=]>()?;
+                        let owner: syn::Expr = input.parse()?;
+                        let is_none =
+                            matches!(&owner, syn::Expr::Path(p) if p.path.is_ident("none"));
+                        args.owner = Some(match is_none {
+                            true => AccountOwner::None,
+                            false => AccountOwner::Expr(owner),
+                        });
+                    }
+                    _ => {
+                        return Err(syn::Error::new(
+                            ident.span(),
+                            "invalid account argument, expected one of: a namespace string \
+                             literal, zero_copy, owner",
+                        ))
+                    }
+                }
+            }
+            if !input.is_empty() {
+                input.parse::<syn::Token![,]>()?;
+            }
+        }
+        Ok(args)
+    }
+}
+
+// Casting account data into the struct is only sound if every field is `Pod`
+// and the `#[repr(C)]` layout has no padding bytes, so both are asserted at
+// compile time before implementing `Pod`.
+fn generate_zero_copy(
+    account_strct: &syn::ItemStruct,
+    discriminator: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let account_name = &account_strct.ident;
+    let field_tys: Vec<&syn::Type> = account_strct.fields.iter().map(|f| &f.ty).collect();
+    quote! {
+        #[derive(Copy, Clone)]
+        #[repr(C)]
+        #account_strct
+
+        const _: fn() = || {
+            fn assert_pod<T: anchor_lang::bytemuck::Pod>() {}
+            #(assert_pod::<#field_tys>();)*
+        };
+        const _: [(); 0] = [(); std::mem::size_of::<#account_name>()
+            - (0 #(+ std::mem::size_of::<#field_tys>())*)];
+
+        unsafe impl anchor_lang::bytemuck::Pod for #account_name {}
+        unsafe impl anchor_lang::bytemuck::Zeroable for #account_name {}
+
+        impl anchor_lang::Discriminator for #account_name {
+            const DISCRIMINATOR: [u8; 8] = #discriminator;
+        }
+
+        impl anchor_lang::ZeroCopy for #account_name {}
+    }
+}
+
+/// Defines the program's `ID`, along with `check_id` and `id` functions
+/// returning it, from a base58 encoded address. Every program must declare
+/// its id at the root of its crate. The id is checked upon entry to the
+/// program and against the program account of every CPI into it.
+///
+/// # Example
+///
+/// ```ignore
+/// use anchor_lang::prelude::*;
+///
+/// declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+/// ```
+#[proc_macro]
+pub fn declare_id(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let id = parse_macro_input!(input as syn::LitStr);
+    let bytes = match bs58::decode(id.value()).into_vec() {
+        Ok(bytes) if bytes.len() == 32 => bytes,
+        _ => {
+            return syn::Error::new(id.span(), "invalid base58 encoded program id")
+                .to_compile_error()
+                .into()
+        }
+    };
+    #[cfg(feature = "idl-build")]
+    let idl_build = anchor_syn::codegen::idl_build::generate_address(&id);
+    #[cfg(not(feature = "idl-build"))]
+    let idl_build = quote! {};
+
+    proc_macro::TokenStream::from(quote! {
+        /// The static program ID.
+        pub static ID: anchor_lang::solana_program::pubkey::Pubkey =
+            anchor_lang::solana_program::pubkey::Pubkey::new_from_array([#(#bytes,)*]);
+
+        /// Confirms that a given pubkey is equivalent to the program ID.
+        pub fn check_id(id: &anchor_lang::solana_program::pubkey::Pubkey) -> bool {
+            id == &ID
+        }
+
+        /// Returns the program ID.
+        pub fn id() -> anchor_lang::solana_program::pubkey::Pubkey {
+            ID
+        }
+
+        #idl_build
     })
 }
@@ -54,3 +54,11 @@ pub fn error(
     let error = error_codegen::generate(error_parser::parse(&mut error_enum));
     proc_macro::TokenStream::from(error)
 }
+
+/// Generates the framework's `ErrorCode`, reserving codes 0-100, from the
+/// codes exported into every IDL. Used only by `anchor_lang`.
+#[doc(hidden)]
+#[proc_macro]
+pub fn framework_error_code(_input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    proc_macro::TokenStream::from(error_codegen::generate_framework_error_code())
+}
@@ -0,0 +1,100 @@
+extern crate proc_macro;
+
+use quote::quote;
+use syn::parse_macro_input;
+
+/// The event attribute allows a struct to be used with
+/// [emit!](./macro.emit.html) so that programs can log significant events in
+/// their programs that clients can subscribe to. Currently, this macro is for
+/// structs only.
+///
+/// Events are serialized with Borsh and prefixed by an 8 byte discriminator,
+/// the first 8 bytes of the SHA256 of `event:<EventName>`.
+///
+/// # Example
+///
+/// ```ignore
+/// #[event]
+/// pub struct MyEvent {
+///     pub data: u64,
+///     pub label: String,
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn event(
+    _args: proc_macro::TokenStream,
+    input: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    let event_strct = parse_macro_input!(input as syn::ItemStruct);
+
+    let event_name = &event_strct.ident;
+
+    let discriminator: proc_macro2::TokenStream = {
+        let discriminator_preimage = format!("event:{}", event_name);
+        let mut discriminator = [0u8; 8];
+        discriminator.copy_from_slice(
+            &anchor_syn::hash::hash(discriminator_preimage.as_bytes()).to_bytes()[..8],
+        );
+        format!("{:?}", discriminator).parse().unwrap()
+    };
+
+    #[cfg(feature = "idl-build")]
+    let idl_build = anchor_syn::codegen::idl_build::generate_event(&event_strct);
+    #[cfg(not(feature = "idl-build"))]
+    let idl_build = quote! {};
+
+    proc_macro::TokenStream::from(quote! {
+        #[derive(AnchorSerialize, AnchorDeserialize)]
+        #event_strct
+
+        impl anchor_lang::Event for #event_name {
+            fn data(&self) -> Vec<u8> {
+                let mut d = #discriminator.to_vec();
+                d.append(&mut anchor_lang::AnchorSerialize::try_to_vec(self).unwrap());
+                d
+            }
+        }
+
+        impl anchor_lang::Discriminator for #event_name {
+            const DISCRIMINATOR: [u8; 8] = #discriminator;
+        }
+
+        #idl_build
+    })
+}
+
+/// Logs an event that can be subscribed to by clients. The discriminator
+/// prefixed event is base64 encoded and written to the program log with
+/// [`msg!`](../solana_program/macro.msg.html).
+///
+/// # Example
+///
+/// ```ignore
+/// use anchor_lang::prelude::*;
+///
+/// // handler function inside #[program]
+/// pub fn initialize(_ctx: Context<Initialize>) -> ProgramResult {
+///     emit!(MyEvent {
+///         data: 5,
+///         label: "hello".to_string(),
+///     });
+///     Ok(())
+/// }
+///
+/// #[event]
+/// pub struct MyEvent {
+///     pub data: u64,
+///     pub label: String,
+/// }
+/// ```
+#[proc_macro]
+pub fn emit(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let data: proc_macro2::TokenStream = input.into();
+    proc_macro::TokenStream::from(quote! {
+        {
+            let data = anchor_lang::Event::data(&#data);
+            let msg_str = &anchor_lang::base64::encode(data);
+            anchor_lang::solana_program::msg!(msg_str);
+        }
+    })
+}
@@ -191,13 +191,13 @@ pub fn interface(
                 if args.is_empty() {
                     quote! {
                         use anchor_lang::prelude::borsh;
-                        #[derive(anchor_lang::AnchorSerialize, anchor_lang::AnchorDeserialize)]
+                        #[derive(anchor_lang::prelude::borsh::BorshSerialize, anchor_lang::prelude::borsh::BorshDeserialize)]
                         struct Args;
                     }
                 } else {
                     quote! {
                         use anchor_lang::prelude::borsh;
-                        #[derive(anchor_lang::AnchorSerialize, anchor_lang::AnchorDeserialize)]
+                        #[derive(anchor_lang::prelude::borsh::BorshSerialize, anchor_lang::prelude::borsh::BorshDeserialize)]
                         struct Args {
                             #(#args),*
                         }
@@ -1,6 +1,8 @@
 extern crate proc_macro;
 
+use anchor_syn::codegen::declare_program as declare_program_codegen;
 use anchor_syn::codegen::program as program_codegen;
+use anchor_syn::idl::Idl;
 use anchor_syn::parser::program as program_parser;
 use syn::parse_macro_input;
 
@@ -15,3 +17,39 @@ pub fn program(
     let code = program_codegen::generate(program_parser::parse(program_mod));
     proc_macro::TokenStream::from(code)
 }
+
+/// Declares a module, named after the program, with a client for a program
+/// generated from its IDL, for programs whose crate isn't available as a
+/// dependency. The path to the IDL file is relative to the crate's root.
+///
+/// The module contains the same `instruction` and `accounts` modules as a
+/// program crate built with the `cpi` feature, where the accounts of each
+/// instruction are named after the instruction, along with its account
+/// structs, types, events and `ErrorCode` enum. If the IDL's metadata has the
+/// program's address, the module also declares its `ID`.
+///
+/// # Example
+///
+/// ```ignore
+/// anchor_lang::declare_program!("idl/basic_1.json");
+///
+/// let ix = basic_1::instruction::Initialize { data: 1234 };
+/// let accounts = basic_1::accounts::Initialize { my_account, rent };
+/// let account: basic_1::MyAccount = program.account(my_account)?;
+/// ```
+#[proc_macro]
+pub fn declare_program(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let path = parse_macro_input!(input as syn::LitStr);
+    let idl = match read_idl(&path.value()) {
+        Ok(idl) => idl,
+        Err(e) => return syn::Error::new(path.span(), e).to_compile_error().into(),
+    };
+    proc_macro::TokenStream::from(declare_program_codegen::generate(&idl))
+}
+
+fn read_idl(path: &str) -> anyhow::Result<Idl> {
+    let path = std::path::Path::new(&std::env::var("CARGO_MANIFEST_DIR")?).join(path);
+    let file = std::fs::File::open(&path)
+        .map_err(|e| anyhow::anyhow!("unable to read {}: {}", path.display(), e))?;
+    Ok(serde_json::from_reader(file)?)
+}
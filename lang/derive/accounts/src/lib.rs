@@ -39,16 +39,34 @@ use syn::parse_macro_input;
 /// | Attribute | Location | Description |
 /// |:--|:--|:--|
 /// | `#[account(signer)]` | On raw `AccountInfo` structs. | Checks the given account signed the transaction. |
-/// | `#[account(mut)]` | On `AccountInfo`, `ProgramAccount` or `CpiAccount` structs. | Marks the account as mutable and persists the state transition. |
-/// | `#[account(init)]` | On `ProgramAccount` structs. | Marks the account as being initialized, skipping the account discriminator check. |
+/// | `#[account(mut)]` | On `AccountInfo`, `Signer`, `ProgramAccount`, `ZeroCopyAccount` or `CpiAccount` structs. | Marks the account as mutable and persists the state transition. |
+/// | `#[account(init)]` | On `ProgramAccount` or `ZeroCopyAccount` structs. | Marks the account as being initialized, skipping the account discriminator check. |
+/// | `#[account(init, payer = <target>, space = <expr>)]` | On `ProgramAccount` structs. | Creates the account via the system program, funded by the `target` account, allocating `expr` bytes (including the 8 byte account discriminator). The struct must have `rent` and `system_program` fields, and the account being created must sign the transaction, unless it's a program derived address given by `seeds`. |
 /// | `#[account(belongs_to = <target>)]` | On `ProgramAccount` or `CpiAccount` structs | Checks the `target` field on the account matches the `target` field in the struct deriving `Accounts`. |
 /// | `#[account(has_one = <target>)]` | On `ProgramAccount` or `CpiAccount` structs | Semantically different, but otherwise the same as `belongs_to`. |
 /// | `#[account(seeds = [<seeds>])]` | On `AccountInfo` structs | Seeds for the program derived address an `AccountInfo` struct represents. |
+/// | `#[account(seeds = [<seeds>], bump)]` | On `AccountInfo` or `ProgramAccount` structs | Finds the canonical bump seed for the program derived address, so that it needn't be given as the last seed. The bump is available to the instruction handler via `ctx.bumps["<field>"]`. |
+/// | `#[account(mut, close = <target>)]` | On `ProgramAccount` structs | Closes the account on exit, sending its lamports to the `target` field and writing a closed account discriminator so that it can't be used again in the same transaction. |
+/// | `#[account(mut, realloc = <expr>, realloc::payer = <target>)]` | Unsupported | Rejected at compile time, since the targeted Solana runtime can't resize account data. Allocate the maximum `space` needed upon `init` instead. |
 /// | `#[account("<literal>")]` | On any type deriving `Accounts` | Executes the given code literal as a constraint. The literal should evaluate to a boolean. |
 /// | `#[account(rent_exempt = <skip>)]` | On `AccountInfo` or `ProgramAccount` structs | Optional attribute to skip the rent exemption check. By default, all accounts marked with `#[account(init)]` will be rent exempt, and so this should rarely (if ever) be used. Similarly, omitting `= skip` will mark the account rent exempt. |
+///
+/// When a constraint is violated, the offending field is logged and a framework
+/// error code reserved for the constraint is returned, e.g., `ConstraintBelongsTo`
+/// for a `belongs_to` violation. These codes are exported in every IDL's
+/// `errors` list so that clients can decode them.
+///
+/// Rather than constraining raw `AccountInfo` fields, the
+/// [`Signer`](./struct.Signer.html) type checks the account signed the
+/// transaction, and [`Program`](./struct.Program.html) checks the account is
+/// the executable program identified by a type implementing
+/// [`Id`](./trait.Id.html), e.g., `Program<'info, System>`.
 #[proc_macro_derive(Accounts, attributes(account))]
 pub fn derive_anchor_deserialize(item: TokenStream) -> TokenStream {
     let strct = parse_macro_input!(item as syn::ItemStruct);
-    let tts = accounts_codegen::generate(accounts_parser::parse(&strct));
+    let tts = match accounts_parser::parse(&strct) {
+        Ok(accs) => accounts_codegen::generate(accs),
+        Err(e) => e.to_compile_error(),
+    };
     proc_macro::TokenStream::from(tts)
 }
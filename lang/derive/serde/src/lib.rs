@@ -0,0 +1,75 @@
+extern crate proc_macro;
+
+use borsh_derive_internal::{enum_de, enum_ser, struct_de, struct_ser, union_de, union_ser};
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{parse_macro_input, Ident};
+
+/// Implements `AnchorSerialize`, i.e., `BorshSerialize`, as its derive does.
+///
+/// With the `idl-build` feature, additionally emits a test printing the
+/// type's definition for the IDL.
+#[proc_macro_derive(AnchorSerialize, attributes(borsh_skip))]
+pub fn anchor_serialize(input: TokenStream) -> TokenStream {
+    let item = parse_macro_input!(input as syn::Item);
+    let res = match &item {
+        syn::Item::Struct(strct) => struct_ser(strct, borsh_crate()),
+        syn::Item::Enum(enm) => enum_ser(enm, borsh_crate()),
+        syn::Item::Union(union) => union_ser(union, borsh_crate()),
+        // Derive macros can only be defined on structs, enums, and unions.
+        _ => unreachable!(),
+    };
+    generate(res, &item, "serialize")
+}
+
+/// Implements `AnchorDeserialize`, i.e., `BorshDeserialize`, as its derive
+/// does.
+///
+/// With the `idl-build` feature, additionally emits a test printing the
+/// type's definition for the IDL.
+#[proc_macro_derive(AnchorDeserialize, attributes(borsh_skip, borsh_init))]
+pub fn anchor_deserialize(input: TokenStream) -> TokenStream {
+    let item = parse_macro_input!(input as syn::Item);
+    let res = match &item {
+        syn::Item::Struct(strct) => struct_de(strct, borsh_crate()),
+        syn::Item::Enum(enm) => enum_de(enm, borsh_crate()),
+        syn::Item::Union(union) => union_de(union, borsh_crate()),
+        // Derive macros can only be defined on structs, enums, and unions.
+        _ => unreachable!(),
+    };
+    generate(res, &item, "deserialize")
+}
+
+// Programs depend on borsh through `anchor_lang::prelude::borsh`, rather than
+// directly. The generated impl refers to this ident, which `generate` brings
+// into scope from that absolute path.
+fn borsh_crate() -> Ident {
+    Ident::new("borsh", Span::call_site())
+}
+
+fn generate(
+    res: syn::Result<proc_macro2::TokenStream>,
+    _item: &syn::Item,
+    _derive: &str,
+) -> TokenStream {
+    let borsh_impl = match res {
+        Ok(borsh_impl) => borsh_impl,
+        Err(e) => return TokenStream::from(e.to_compile_error()),
+    };
+
+    #[cfg(feature = "idl-build")]
+    let idl_build = anchor_syn::codegen::idl_build::generate_type(_item, _derive);
+    #[cfg(not(feature = "idl-build"))]
+    let idl_build = quote! {};
+
+    TokenStream::from(quote! {
+        const _: () = {
+            use ::anchor_lang::prelude::borsh;
+
+            #borsh_impl
+        };
+
+        #idl_build
+    })
+}
@@ -4,6 +4,7 @@ use solana_program::entrypoint::ProgramResult;
 use solana_program::instruction::AccountMeta;
 use solana_program::program_error::ProgramError;
 use solana_program::pubkey::Pubkey;
+use std::collections::BTreeMap;
 use std::ops::Deref;
 
 impl<'info, T: Accounts<'info>> Accounts<'info> for Box<T> {
@@ -13,6 +14,14 @@ impl<'info, T: Accounts<'info>> Accounts<'info> for Box<T> {
     ) -> Result<Self, ProgramError> {
         T::try_accounts(program_id, accounts).map(Box::new)
     }
+
+    fn try_accounts_with_bumps(
+        program_id: &Pubkey,
+        accounts: &mut &[AccountInfo<'info>],
+        bumps: &mut BTreeMap<String, u8>,
+    ) -> Result<Self, ProgramError> {
+        T::try_accounts_with_bumps(program_id, accounts, bumps).map(Box::new)
+    }
 }
 
 impl<'info, T: AccountsExit<'info>> AccountsExit<'info> for Box<T> {
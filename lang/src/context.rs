@@ -1,6 +1,7 @@
 use crate::{Accounts, ToAccountInfos, ToAccountMetas};
 use solana_program::account_info::AccountInfo;
 use solana_program::pubkey::Pubkey;
+use std::collections::BTreeMap;
 
 /// Provides non-argument inputs to the program.
 pub struct Context<'a, 'b, 'c, 'info, T> {
@@ -10,6 +11,11 @@ pub struct Context<'a, 'b, 'c, 'info, T> {
     pub accounts: &'b mut T,
     /// Remaining accounts given but not deserialized or validated.
     pub remaining_accounts: &'c [AccountInfo<'info>],
+    /// Canonical bump seeds found for each account with a
+    /// `#[account(seeds = [..], bump)]` constraint, keyed by field name.
+    /// Fields of composite accounts are prefixed by the composite field's
+    /// name, e.g., `inner.vault`.
+    pub bumps: BTreeMap<String, u8>,
 }
 
 impl<'a, 'b, 'c, 'info, T: Accounts<'info>> Context<'a, 'b, 'c, 'info, T> {
@@ -17,11 +23,13 @@ impl<'a, 'b, 'c, 'info, T: Accounts<'info>> Context<'a, 'b, 'c, 'info, T> {
         program_id: &'a Pubkey,
         accounts: &'b mut T,
         remaining_accounts: &'c [AccountInfo<'info>],
+        bumps: BTreeMap<String, u8>,
     ) -> Self {
         Self {
             accounts,
             program_id,
             remaining_accounts,
+            bumps,
         }
     }
 }
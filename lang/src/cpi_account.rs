@@ -1,5 +1,6 @@
 use crate::{
-    AccountDeserialize, Accounts, AccountsExit, ToAccountInfo, ToAccountInfos, ToAccountMetas,
+    AccountDeserialize, Accounts, AccountsExit, ErrorCode, Owner, ToAccountInfo, ToAccountInfos,
+    ToAccountMetas,
 };
 use solana_program::account_info::AccountInfo;
 use solana_program::entrypoint::ProgramResult;
@@ -43,7 +44,7 @@ impl<'a, T: AccountDeserialize + Clone> CpiAccount<'a, T> {
 
 impl<'info, T> Accounts<'info> for CpiAccount<'info, T>
 where
-    T: AccountDeserialize + Clone,
+    T: AccountDeserialize + Owner + Clone,
 {
     #[inline(never)]
     fn try_accounts(
@@ -55,6 +56,12 @@ where
         }
         let account = &accounts[0];
         *accounts = &accounts[1..];
+        if let Some(owner) = T::owner() {
+            if account.owner != &owner {
+                solana_program::msg!("{}: {}", ErrorCode::ConstraintOwner.msg(), account.key);
+                return Err(ErrorCode::ConstraintOwner.into());
+            }
+        }
         let pa = CpiAccount::try_from(account)?;
         Ok(pa)
     }
@@ -8,11 +8,9 @@ pub enum Error {
     ErrorCode(#[from] ErrorCode),
 }
 
-#[derive(Debug, Clone, Copy)]
-#[repr(u32)]
-pub enum ErrorCode {
-    WrongSerialization = 1,
-}
+// The framework's `ErrorCode`, generated from `anchor_syn::FRAMEWORK_ERROR_CODES`
+// so that it matches the codes exported into every IDL.
+anchor_attribute_error::framework_error_code!();
 
 impl std::fmt::Display for ErrorCode {
     fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
@@ -30,3 +28,10 @@ impl std::convert::From<Error> for ProgramError {
         }
     }
 }
+
+impl std::convert::From<ErrorCode> for ProgramError {
+    fn from(e: ErrorCode) -> ProgramError {
+        let err: Error = e.into();
+        err.into()
+    }
+}
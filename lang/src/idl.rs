@@ -48,8 +48,9 @@ pub struct IdlAccounts<'info> {
 }
 
 // The account holding a program's IDL. This is stored on chain so that clients
-// can fetch it and generate a client with nothing but a program's ID.
-#[account]
+// can fetch it and generate a client with nothing but a program's ID. The
+// owner is the program whose IDL is stored, so it isn't known at compile time.
+#[account(owner = none)]
 #[derive(Debug)]
 pub struct IdlAccount {
     // Address that can modify the IDL.
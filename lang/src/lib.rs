@@ -27,6 +27,7 @@ use solana_program::account_info::AccountInfo;
 use solana_program::instruction::AccountMeta;
 use solana_program::program_error::ProgramError;
 use solana_program::pubkey::Pubkey;
+use std::collections::BTreeMap;
 use std::io::Write;
 
 mod account_info;
@@ -36,27 +37,51 @@ mod cpi_account;
 mod ctor;
 mod error;
 pub mod idl;
+mod program;
 mod program_account;
+mod signer;
 mod state;
+mod system_program;
 mod sysvar;
 mod vec;
+mod zero_copy_account;
 
 pub use crate::context::{Context, CpiContext};
 pub use crate::cpi_account::CpiAccount;
 pub use crate::ctor::Ctor;
+pub use crate::program::Program;
 pub use crate::program_account::ProgramAccount;
+pub use crate::signer::Signer;
 pub use crate::state::ProgramState;
+pub use crate::system_program::System;
 pub use crate::sysvar::Sysvar;
+pub use crate::zero_copy_account::ZeroCopyAccount;
 pub use anchor_attribute_access_control::access_control;
-pub use anchor_attribute_account::account;
+pub use anchor_attribute_account::{account, declare_id};
 pub use anchor_attribute_error::error;
+pub use anchor_attribute_event::{emit, event};
 pub use anchor_attribute_interface::interface;
-pub use anchor_attribute_program::program;
+pub use anchor_attribute_program::{declare_program, program};
 pub use anchor_attribute_state::state;
 pub use anchor_derive_accounts::Accounts;
+pub use anchor_derive_serde::{AnchorDeserialize, AnchorSerialize};
+/// The IDL types and JSON serialization used by the code generated for the
+/// `idl-build` feature.
+#[cfg(feature = "idl-build")]
+pub use anchor_syn;
+/// Base64 is used to encode events written to the program log.
+pub use base64;
 /// Borsh is the default serialization format for instructions and accounts.
-pub use borsh::{BorshDeserialize as AnchorDeserialize, BorshSerialize as AnchorSerialize};
-pub use error::Error;
+/// The traits are derived with Anchor's own macros, which additionally export
+/// the type into the IDL under the `idl-build` feature.
+pub use borsh::{
+    de::BorshDeserialize as AnchorDeserialize, ser::BorshSerialize as AnchorSerialize,
+};
+/// Bytemuck is used to cast the data of zero copy accounts.
+pub use bytemuck;
+pub use error::{Error, ErrorCode};
+#[cfg(feature = "idl-build")]
+pub use serde_json;
 pub use solana_program;
 
 /// A data structure of validated accounts that can be deserialized from the
@@ -81,6 +106,28 @@ pub trait Accounts<'info>: ToAccountMetas + ToAccountInfos<'info> + Sized {
         program_id: &Pubkey,
         accounts: &mut &[AccountInfo<'info>],
     ) -> Result<Self, ProgramError>;
+
+    /// Same as `try_accounts`, additionally recording the canonical bump seed
+    /// found for each `#[account(seeds = [..], bump)]` constraint into `bumps`,
+    /// keyed by field name, prefixed by the names of any composite fields
+    /// containing it, e.g., `inner.vault`. These are given to instruction
+    /// handlers via the [`Context`](./struct.Context.html).
+    fn try_accounts_with_bumps(
+        program_id: &Pubkey,
+        accounts: &mut &[AccountInfo<'info>],
+        _bumps: &mut BTreeMap<String, u8>,
+    ) -> Result<Self, ProgramError> {
+        Self::try_accounts(program_id, accounts)
+    }
+
+    /// Returns the accounts described in the IDL, generated by the `Accounts`
+    /// derive under the `idl-build` feature. Hand-written implementations
+    /// aren't described and have no accounts in the IDL.
+    #[cfg(feature = "idl-build")]
+    #[doc(hidden)]
+    fn __anchor_private_idl_accounts() -> Vec<anchor_syn::idl::IdlAccountItem> {
+        Vec::new()
+    }
 }
 
 /// The exit procedure for an account. Any cleanup or persistance to storage
@@ -90,6 +137,21 @@ pub trait AccountsExit<'info>: ToAccountMetas + ToAccountInfos<'info> {
     fn exit(&self, program_id: &Pubkey) -> solana_program::entrypoint::ProgramResult;
 }
 
+/// The close procedure for an account, replacing [`AccountsExit`] for fields
+/// marked with `#[account(close = <target>)]`.
+pub trait AccountsClose<'info>: ToAccountInfos<'info> {
+    /// Sends all of the account's lamports to `sol_destination` and zeroes its
+    /// data, writing [`CLOSED_ACCOUNT_DISCRIMINATOR`] so that the account can't
+    /// be deserialized or re-initialized for the rest of the transaction.
+    fn close(
+        &self,
+        sol_destination: AccountInfo<'info>,
+    ) -> solana_program::entrypoint::ProgramResult;
+}
+
+/// Discriminator written to accounts closed by [`AccountsClose`].
+pub const CLOSED_ACCOUNT_DISCRIMINATOR: [u8; 8] = [255, 255, 255, 255, 255, 255, 255, 255];
+
 /// A data structure of accounts providing a one time deserialization upon
 /// account initialization, i.e., when the data array for a given account is
 /// zeroed. Any subsequent call to `try_accounts_init` should fail. For all
@@ -161,6 +223,39 @@ pub trait AccountDeserialize: Sized {
     fn try_deserialize_unchecked(buf: &mut &[u8]) -> Result<Self, ProgramError>;
 }
 
+/// The 8 byte account discriminator, `Sha256("account:<AccountName>")[..8]`,
+/// prefixing the data of an account. Implemented by `#[account]` structs.
+pub trait Discriminator {
+    const DISCRIMINATOR: [u8; 8];
+}
+
+/// An event that can be written to the program log with the
+/// [`emit!`](./macro.emit.html) macro. Implemented by `#[event]` structs.
+pub trait Event: AnchorSerialize + AnchorDeserialize + Discriminator {
+    /// Returns the discriminator prefixed, serialized event.
+    fn data(&self) -> Vec<u8>;
+}
+
+/// The program owning an account type, validated by [`CpiAccount`] upon
+/// deserialization. Implemented by `#[account]` structs, returning the crate's
+/// `ID` or the program given by `#[account(owner = <expr>)]`. When `None`, the
+/// owner isn't known at compile time and should be checked with a constraint
+/// instead.
+pub trait Owner {
+    fn owner() -> Option<Pubkey>;
+}
+
+/// The address of a program, validated by [`Program`] upon deserialization.
+/// Implemented by the program's marker type, e.g., [`System`].
+pub trait Id {
+    fn id() -> Pubkey;
+}
+
+/// An account whose data is cast directly into a `#[repr(C)]` struct, rather
+/// than deserialized, via [`ZeroCopyAccount`]. Implemented by
+/// `#[account(zero_copy)]` structs.
+pub trait ZeroCopy: Discriminator + bytemuck::Pod + bytemuck::Zeroable {}
+
 /// Calculates the data for an instruction invocation, where the data is
 /// `Sha256(<namespace>::<method_name>)[..8] || BorshSerialize(args)`.
 /// `args` is a borsh serialized struct of named fields for each argument given
@@ -173,10 +268,11 @@ pub trait InstructionData: AnchorSerialize {
 /// All programs should include it via `anchor_lang::prelude::*;`.
 pub mod prelude {
     pub use super::{
-        access_control, account, error, interface, program, state, AccountDeserialize,
-        AccountSerialize, Accounts, AccountsExit, AccountsInit, AnchorDeserialize, AnchorSerialize,
-        Context, CpiAccount, CpiContext, Ctor, ProgramAccount, ProgramState, Sysvar, ToAccountInfo,
-        ToAccountInfos, ToAccountMetas,
+        access_control, account, declare_id, declare_program, emit, error, event, interface,
+        program, state, AccountDeserialize, AccountSerialize, Accounts, AccountsClose,
+        AccountsExit, AccountsInit, AnchorDeserialize, AnchorSerialize, Context, CpiAccount,
+        CpiContext, Ctor, Discriminator, Id, Owner, Program, ProgramAccount, ProgramState, Signer,
+        System, Sysvar, ToAccountInfo, ToAccountInfos, ToAccountMetas, ZeroCopy, ZeroCopyAccount,
     };
 
     pub use borsh;
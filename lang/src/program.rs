@@ -0,0 +1,96 @@
+use crate::{Accounts, AccountsExit, ErrorCode, Id, ToAccountInfo, ToAccountInfos, ToAccountMetas};
+use solana_program::account_info::AccountInfo;
+use solana_program::entrypoint::ProgramResult;
+use solana_program::instruction::AccountMeta;
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+use std::marker::PhantomData;
+use std::ops::Deref;
+
+/// Container for an executable program account, whose address is the one
+/// given by `T`. Useful as the target of CPI, e.g., `Program<'info, Token>`
+/// rather than an `AccountInfo` with a literal constraint on its key.
+#[derive(Clone)]
+pub struct Program<'info, T: Id + Clone> {
+    info: AccountInfo<'info>,
+    _phantom: PhantomData<T>,
+}
+
+impl<'info, T: Id + Clone> Program<'info, T> {
+    fn new(info: AccountInfo<'info>) -> Program<'info, T> {
+        Self {
+            info,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Checks the given `info` is the executable program account of `T`.
+    pub fn try_from(info: &AccountInfo<'info>) -> Result<Program<'info, T>, ProgramError> {
+        if info.key != &T::id() {
+            solana_program::msg!("{}: {}", ErrorCode::InvalidProgramId.msg(), info.key);
+            return Err(ErrorCode::InvalidProgramId.into());
+        }
+        if !info.executable {
+            solana_program::msg!(
+                "{}: {}",
+                ErrorCode::InvalidProgramExecutable.msg(),
+                info.key
+            );
+            return Err(ErrorCode::InvalidProgramExecutable.into());
+        }
+        Ok(Program::new(info.clone()))
+    }
+}
+
+impl<'info, T: Id + Clone> Accounts<'info> for Program<'info, T> {
+    #[inline(never)]
+    fn try_accounts(
+        _program_id: &Pubkey,
+        accounts: &mut &[AccountInfo<'info>],
+    ) -> Result<Self, ProgramError> {
+        if accounts.is_empty() {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+        let account = &accounts[0];
+        *accounts = &accounts[1..];
+        Program::try_from(account)
+    }
+}
+
+impl<'info, T: Id + Clone> ToAccountMetas for Program<'info, T> {
+    fn to_account_metas(&self, is_signer: Option<bool>) -> Vec<AccountMeta> {
+        let is_signer = is_signer.unwrap_or(self.info.is_signer);
+        let meta = match self.info.is_writable {
+            false => AccountMeta::new_readonly(*self.info.key, is_signer),
+            true => AccountMeta::new(*self.info.key, is_signer),
+        };
+        vec![meta]
+    }
+}
+
+impl<'info, T: Id + Clone> ToAccountInfos<'info> for Program<'info, T> {
+    fn to_account_infos(&self) -> Vec<AccountInfo<'info>> {
+        vec![self.info.clone()]
+    }
+}
+
+impl<'info, T: Id + Clone> ToAccountInfo<'info> for Program<'info, T> {
+    fn to_account_info(&self) -> AccountInfo<'info> {
+        self.info.clone()
+    }
+}
+
+impl<'info, T: Id + Clone> Deref for Program<'info, T> {
+    type Target = AccountInfo<'info>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.info
+    }
+}
+
+impl<'info, T: Id + Clone> AccountsExit<'info> for Program<'info, T> {
+    fn exit(&self, _program_id: &Pubkey) -> ProgramResult {
+        // no-op
+        Ok(())
+    }
+}
@@ -1,12 +1,14 @@
 use crate::{
-    AccountDeserialize, AccountSerialize, Accounts, AccountsExit, AccountsInit, CpiAccount,
-    ToAccountInfo, ToAccountInfos, ToAccountMetas,
+    AccountDeserialize, AccountSerialize, Accounts, AccountsClose, AccountsExit, AccountsInit,
+    CpiAccount, ErrorCode, ToAccountInfo, ToAccountInfos, ToAccountMetas,
+    CLOSED_ACCOUNT_DISCRIMINATOR,
 };
 use solana_program::account_info::AccountInfo;
 use solana_program::entrypoint::ProgramResult;
 use solana_program::instruction::AccountMeta;
 use solana_program::program_error::ProgramError;
 use solana_program::pubkey::Pubkey;
+use std::io::Write;
 use std::ops::{Deref, DerefMut};
 
 /// Boxed container for a deserialized `account`. Use this to reference any
@@ -110,7 +112,39 @@ impl<'info, T: AccountSerialize + AccountDeserialize + Clone> AccountsExit<'info
         let mut data = info.try_borrow_mut_data()?;
         let dst: &mut [u8] = &mut data;
         let mut cursor = std::io::Cursor::new(dst);
-        self.inner.account.try_serialize(&mut cursor)?;
+        // Account data can't be resized, so a `Vec` or `String` grown past the
+        // original allocation will fail here.
+        if self.inner.account.try_serialize(&mut cursor).is_err() {
+            solana_program::msg!("{}: {}", ErrorCode::AccountDidNotSerialize.msg(), info.key);
+            return Err(ErrorCode::AccountDidNotSerialize.into());
+        }
+        Ok(())
+    }
+}
+
+impl<'info, T: AccountSerialize + AccountDeserialize + Clone> AccountsClose<'info>
+    for ProgramAccount<'info, T>
+{
+    fn close(&self, sol_destination: AccountInfo<'info>) -> ProgramResult {
+        let info = self.to_account_info();
+
+        // Transfer all lamports to the destination.
+        let dest_starting_lamports = sol_destination.lamports();
+        **sol_destination.lamports.borrow_mut() = dest_starting_lamports
+            .checked_add(info.lamports())
+            .ok_or(ProgramError::InvalidArgument)?;
+        **info.lamports.borrow_mut() = 0;
+
+        // Zero the data and mark the account as closed.
+        let mut data = info.try_borrow_mut_data()?;
+        for byte in data.iter_mut() {
+            *byte = 0;
+        }
+        let dst: &mut [u8] = &mut data;
+        let mut cursor = std::io::Cursor::new(dst);
+        cursor
+            .write_all(&CLOSED_ACCOUNT_DISCRIMINATOR)
+            .map_err(|_| ErrorCode::WrongSerialization)?;
         Ok(())
     }
 }
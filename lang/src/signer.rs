@@ -0,0 +1,88 @@
+use crate::{Accounts, AccountsExit, ErrorCode, ToAccountInfo, ToAccountInfos, ToAccountMetas};
+use solana_program::account_info::AccountInfo;
+use solana_program::entrypoint::ProgramResult;
+use solana_program::instruction::AccountMeta;
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+use std::ops::Deref;
+
+/// Container for an account that signed the transaction, replacing an
+/// `AccountInfo` with a `signer` constraint.
+#[derive(Clone)]
+pub struct Signer<'info> {
+    info: AccountInfo<'info>,
+}
+
+impl<'info> Signer<'info> {
+    fn new(info: AccountInfo<'info>) -> Signer<'info> {
+        Self { info }
+    }
+
+    /// Checks the given `info` signed the transaction.
+    pub fn try_from(info: &AccountInfo<'info>) -> Result<Signer<'info>, ProgramError> {
+        if !info.is_signer {
+            return Err(ErrorCode::ConstraintSigner.into());
+        }
+        Ok(Signer::new(info.clone()))
+    }
+
+    // Used by `#[derive(Accounts)]`, which checks the signature with the
+    // generated signer constraint instead, so that it's skipped on CPI.
+    #[doc(hidden)]
+    pub fn new_unchecked(info: &AccountInfo<'info>) -> Signer<'info> {
+        Signer::new(info.clone())
+    }
+}
+
+impl<'info> Accounts<'info> for Signer<'info> {
+    #[inline(never)]
+    fn try_accounts(
+        _program_id: &Pubkey,
+        accounts: &mut &[AccountInfo<'info>],
+    ) -> Result<Self, ProgramError> {
+        if accounts.is_empty() {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+        let account = &accounts[0];
+        *accounts = &accounts[1..];
+        Signer::try_from(account)
+    }
+}
+
+impl<'info> ToAccountMetas for Signer<'info> {
+    fn to_account_metas(&self, is_signer: Option<bool>) -> Vec<AccountMeta> {
+        let is_signer = is_signer.unwrap_or(self.info.is_signer);
+        let meta = match self.info.is_writable {
+            false => AccountMeta::new_readonly(*self.info.key, is_signer),
+            true => AccountMeta::new(*self.info.key, is_signer),
+        };
+        vec![meta]
+    }
+}
+
+impl<'info> ToAccountInfos<'info> for Signer<'info> {
+    fn to_account_infos(&self) -> Vec<AccountInfo<'info>> {
+        vec![self.info.clone()]
+    }
+}
+
+impl<'info> ToAccountInfo<'info> for Signer<'info> {
+    fn to_account_info(&self) -> AccountInfo<'info> {
+        self.info.clone()
+    }
+}
+
+impl<'info> Deref for Signer<'info> {
+    type Target = AccountInfo<'info>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.info
+    }
+}
+
+impl<'info> AccountsExit<'info> for Signer<'info> {
+    fn exit(&self, _program_id: &Pubkey) -> ProgramResult {
+        // no-op
+        Ok(())
+    }
+}
@@ -0,0 +1,12 @@
+use crate::Id;
+use solana_program::pubkey::Pubkey;
+
+/// The native system program, for use as `Program<'info, System>`.
+#[derive(Clone)]
+pub struct System;
+
+impl Id for System {
+    fn id() -> Pubkey {
+        solana_program::system_program::ID
+    }
+}
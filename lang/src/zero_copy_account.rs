@@ -0,0 +1,188 @@
+use crate::{
+    Accounts, AccountsExit, AccountsInit, ErrorCode, ToAccountInfo, ToAccountInfos, ToAccountMetas,
+    ZeroCopy,
+};
+use solana_program::account_info::AccountInfo;
+use solana_program::entrypoint::ProgramResult;
+use solana_program::instruction::AccountMeta;
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+use std::cell::{Ref, RefMut};
+use std::io::Write;
+use std::marker::PhantomData;
+use std::mem;
+use std::ops::Range;
+
+/// Container for a zero copy `account`, i.e., one defined with
+/// `#[account(zero_copy)]`. Unlike [`ProgramAccount`](./struct.ProgramAccount.html),
+/// the account data is never deserialized. Instead, `load`, `load_mut`, and
+/// `load_init` cast the account's data directly into a reference to `T`, which
+/// makes it suitable for accounts too large to copy onto the stack or heap.
+#[derive(Clone)]
+pub struct ZeroCopyAccount<'info, T: ZeroCopy> {
+    acc_info: AccountInfo<'info>,
+    phantom: PhantomData<&'info T>,
+}
+
+impl<'info, T: ZeroCopy> ZeroCopyAccount<'info, T> {
+    fn new(acc_info: AccountInfo<'info>) -> ZeroCopyAccount<'info, T> {
+        Self {
+            acc_info,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Constructs a new `ZeroCopyAccount` from a previously initialized account.
+    #[inline(never)]
+    pub fn try_from(
+        acc_info: &AccountInfo<'info>,
+    ) -> Result<ZeroCopyAccount<'info, T>, ProgramError> {
+        let data: &[u8] = &acc_info.try_borrow_data()?;
+        check_data::<T>(data, &T::DISCRIMINATOR)?;
+        Ok(ZeroCopyAccount::new(acc_info.clone()))
+    }
+
+    /// Constructs a new `ZeroCopyAccount` from an uninitialized account, i.e.,
+    /// one with a zeroed discriminator.
+    #[inline(never)]
+    pub fn try_from_init(
+        acc_info: &AccountInfo<'info>,
+    ) -> Result<ZeroCopyAccount<'info, T>, ProgramError> {
+        let data: &[u8] = &acc_info.try_borrow_data()?;
+        check_data::<T>(data, &[0; 8])?;
+        Ok(ZeroCopyAccount::new(acc_info.clone()))
+    }
+
+    /// Returns a `Ref` to the account data structure for reading.
+    pub fn load(&self) -> Result<Ref<T>, ProgramError> {
+        let data = self.acc_info.try_borrow_data()?;
+        let range = check_data::<T>(&data, &T::DISCRIMINATOR)?;
+        Ok(Ref::map(data, |data| bytemuck::from_bytes(&data[range])))
+    }
+
+    /// Returns a `RefMut` to the account data structure for reading or writing.
+    pub fn load_mut(&self) -> Result<RefMut<T>, ProgramError> {
+        // AccountInfo api allows you to borrow mut even if the account isn't
+        // writable, so add this check for a better dev experience.
+        if !self.acc_info.is_writable {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let data = self.acc_info.try_borrow_mut_data()?;
+        let range = check_data::<T>(&data, &T::DISCRIMINATOR)?;
+        Ok(RefMut::map(data, |data| {
+            bytemuck::from_bytes_mut(&mut data[range])
+        }))
+    }
+
+    /// Returns a `RefMut` to the account data structure for reading or writing
+    /// upon initialization, i.e., when the discriminator is still zeroed. The
+    /// discriminator is written on exit.
+    pub fn load_init(&self) -> Result<RefMut<T>, ProgramError> {
+        if !self.acc_info.is_writable {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let data = self.acc_info.try_borrow_mut_data()?;
+        let range = check_data::<T>(&data, &[0; 8])?;
+        Ok(RefMut::map(data, |data| {
+            bytemuck::from_bytes_mut(&mut data[range])
+        }))
+    }
+}
+
+// Checks the account data is prefixed by the given discriminator and followed
+// by bytes castable into `T`, returning their range, so that casting the data
+// afterwards can't panic.
+fn check_data<T: ZeroCopy>(
+    data: &[u8],
+    discriminator: &[u8; 8],
+) -> Result<Range<usize>, ProgramError> {
+    let range = discriminator.len()..discriminator.len() + mem::size_of::<T>();
+    let bytes = data
+        .get(range.clone())
+        .ok_or(ProgramError::AccountDataTooSmall)?;
+    if &data[..8] != discriminator {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    bytemuck::try_from_bytes::<T>(bytes).map_err(|e| {
+        solana_program::msg!("Unable to cast the account data: {:?}", e);
+        ProgramError::InvalidAccountData
+    })?;
+    Ok(range)
+}
+
+impl<'info, T: ZeroCopy> Accounts<'info> for ZeroCopyAccount<'info, T> {
+    #[inline(never)]
+    fn try_accounts(
+        program_id: &Pubkey,
+        accounts: &mut &[AccountInfo<'info>],
+    ) -> Result<Self, ProgramError> {
+        if accounts.is_empty() {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+        let account = &accounts[0];
+        *accounts = &accounts[1..];
+        let l = ZeroCopyAccount::try_from(account)?;
+        if l.acc_info.owner != program_id {
+            solana_program::msg!("{}: {}", ErrorCode::ConstraintOwner.msg(), account.key);
+            return Err(ErrorCode::ConstraintOwner.into());
+        }
+        Ok(l)
+    }
+}
+
+impl<'info, T: ZeroCopy> AccountsInit<'info> for ZeroCopyAccount<'info, T> {
+    #[inline(never)]
+    fn try_accounts_init(
+        program_id: &Pubkey,
+        accounts: &mut &[AccountInfo<'info>],
+    ) -> Result<Self, ProgramError> {
+        if accounts.is_empty() {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+        let account = &accounts[0];
+        *accounts = &accounts[1..];
+        let l = ZeroCopyAccount::try_from_init(account)?;
+        if l.acc_info.owner != program_id {
+            solana_program::msg!("{}: {}", ErrorCode::ConstraintOwner.msg(), account.key);
+            return Err(ErrorCode::ConstraintOwner.into());
+        }
+        Ok(l)
+    }
+}
+
+impl<'info, T: ZeroCopy> AccountsExit<'info> for ZeroCopyAccount<'info, T> {
+    // The account data is modified in place, so only the discriminator needs
+    // to be written, in case the account was just initialized.
+    fn exit(&self, _program_id: &Pubkey) -> ProgramResult {
+        let mut data = self.acc_info.try_borrow_mut_data()?;
+        let dst: &mut [u8] = &mut data;
+        let mut cursor = std::io::Cursor::new(dst);
+        cursor
+            .write_all(&T::DISCRIMINATOR)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+        Ok(())
+    }
+}
+
+impl<'info, T: ZeroCopy> ToAccountMetas for ZeroCopyAccount<'info, T> {
+    fn to_account_metas(&self, is_signer: Option<bool>) -> Vec<AccountMeta> {
+        let is_signer = is_signer.unwrap_or(self.acc_info.is_signer);
+        let meta = match self.acc_info.is_writable {
+            false => AccountMeta::new_readonly(*self.acc_info.key, is_signer),
+            true => AccountMeta::new(*self.acc_info.key, is_signer),
+        };
+        vec![meta]
+    }
+}
+
+impl<'info, T: ZeroCopy> ToAccountInfos<'info> for ZeroCopyAccount<'info, T> {
+    fn to_account_infos(&self) -> Vec<AccountInfo<'info>> {
+        vec![self.acc_info.clone()]
+    }
+}
+
+impl<'info, T: ZeroCopy> ToAccountInfo<'info> for ZeroCopyAccount<'info, T> {
+    fn to_account_info(&self) -> AccountInfo<'info> {
+        self.acc_info.clone()
+    }
+}
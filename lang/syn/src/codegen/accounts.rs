@@ -1,7 +1,7 @@
 use crate::{
-    AccountField, AccountsStruct, CompositeField, Constraint, ConstraintBelongsTo,
-    ConstraintLiteral, ConstraintOwner, ConstraintRentExempt, ConstraintSeeds, ConstraintSigner,
-    Field, Ty,
+    AccountField, AccountsStruct, CompositeField, Constraint, ConstraintBelongsTo, ConstraintClose,
+    ConstraintInit, ConstraintLiteral, ConstraintOwner, ConstraintRentExempt, ConstraintSeeds,
+    ConstraintSigner, Field, Ty,
 };
 use heck::SnakeCase;
 use quote::quote;
@@ -12,20 +12,48 @@ pub fn generate(accs: AccountsStruct) -> proc_macro2::TokenStream {
         .fields
         .iter()
         .map(|af: &AccountField| match af {
+            // Bumps of the composite's accounts are prefixed by its field
+            // name, e.g., `inner.vault`.
             AccountField::AccountsStruct(s) => {
                 let name = &s.ident;
                 let ty = &s.raw_field.ty;
+                let prefix = format!("{}.", name);
                 quote! {
-                    let #name: #ty = anchor_lang::Accounts::try_accounts(program_id, accounts)?;
+                    let #name: #ty = {
+                        let mut composite_bumps = std::collections::BTreeMap::new();
+                        let #name = anchor_lang::Accounts::try_accounts_with_bumps(program_id, accounts, &mut composite_bumps)?;
+                        for (field, bump) in composite_bumps {
+                            bumps.insert(format!("{}{}", #prefix, field), bump);
+                        }
+                        #name
+                    };
                 }
             }
             AccountField::Field(f) => {
                 let name = f.typed_ident();
-                match f.is_init {
-                    false => quote! {
+                match (f.is_init, f.init_constraint()) {
+                    // The signature is checked by the field's signer
+                    // constraint.
+                    (false, _) if f.ty == Ty::Signer => quote! {
+                        let #name = {
+                            let info: anchor_lang::solana_program::account_info::AccountInfo =
+                                anchor_lang::Accounts::try_accounts(program_id, accounts)?;
+                            anchor_lang::Signer::new_unchecked(&info)
+                        };
+                    },
+                    (false, _) => quote! {
                         let #name = anchor_lang::Accounts::try_accounts(program_id, accounts)?;
                     },
-                    true => quote! {
+                    // The account doesn't exist yet, so take the raw account
+                    // info. It's created and deserialized below.
+                    (true, Some(_)) => {
+                        let ident = &f.ident;
+                        quote! {
+                            let #ident: anchor_lang::solana_program::account_info::AccountInfo =
+                                anchor_lang::Accounts::try_accounts(program_id, accounts)?;
+                        }
+                    }
+                    (true, None) => quote! {
                         let #name = anchor_lang::AccountsInit::try_accounts_init(program_id, accounts)?;
                     },
                 }
@@ -33,6 +61,16 @@ pub fn generate(accs: AccountsStruct) -> proc_macro2::TokenStream {
         })
         .collect();
 
+    // Creation of each account paid for by the program.
+    let init_fields: Vec<proc_macro2::TokenStream> = accs
+        .fields
+        .iter()
+        .filter_map(|af: &AccountField| match af {
+            AccountField::AccountsStruct(_) => None,
+            AccountField::Field(f) => f.init_constraint().map(|c| generate_init(f, c)),
+        })
+        .collect();
+
     // Constraint checks for each account fields.
     let access_checks: Vec<proc_macro2::TokenStream> = accs
         .fields
@@ -84,11 +122,21 @@ pub fn generate(accs: AccountsStruct) -> proc_macro2::TokenStream {
             }
             AccountField::Field(f) => {
                 let ident = &f.ident;
-                match f.is_mut {
-                    false => quote! {},
-                    true => quote! {
+                match (f.is_mut, f.close_constraint()) {
+                    (false, _) => quote! {},
+                    (true, None) => quote! {
                         anchor_lang::AccountsExit::exit(&self.#ident, program_id)?;
                     },
+                    // Closed accounts aren't persisted.
+                    (true, Some(c)) => {
+                        let sol_dest = &c.sol_dest;
+                        quote! {
+                            anchor_lang::AccountsClose::close(
+                                &self.#ident,
+                                self.#sol_dest.to_account_info(),
+                            )?;
+                        }
+                    }
                 }
             }
         })
@@ -152,13 +200,7 @@ pub fn generate(accs: AccountsStruct) -> proc_macro2::TokenStream {
         .map(|f: &AccountField| match f {
             AccountField::AccountsStruct(s) => {
                 let name = &s.ident;
-                let symbol: proc_macro2::TokenStream = format!(
-                    "__client_accounts_{0}::{1}",
-                    s.symbol.to_snake_case(),
-                    s.symbol,
-                )
-                .parse()
-                .unwrap();
+                let symbol = generate_client_accounts_path(s);
                 quote! {
                     pub #name: #symbol
                 }
@@ -211,11 +253,7 @@ pub fn generate(accs: AccountsStruct) -> proc_macro2::TokenStream {
             AccountField::AccountsStruct(s) => Some(s),
             AccountField::Field(_) => None,
         }) {
-            re_exports.insert(format!(
-                "__client_accounts_{0}::{1}",
-                f.symbol.to_snake_case(),
-                f.symbol,
-            ));
+            re_exports.insert(generate_client_accounts_path(f).to_string());
         }
 
         re_exports
@@ -229,13 +267,18 @@ pub fn generate(accs: AccountsStruct) -> proc_macro2::TokenStream {
             .collect()
     };
 
+    #[cfg(feature = "idl-build")]
+    let idl_build = crate::codegen::idl_build::generate_accounts(&accs);
+    #[cfg(not(feature = "idl-build"))]
+    let idl_build = quote! {};
+
     quote! {
-        mod #account_mod_name {
+        pub mod #account_mod_name {
             use super::*;
             use anchor_lang::prelude::borsh;
             #(#re_exports)*
 
-            #[derive(anchor_lang::AnchorSerialize)]
+            #[derive(anchor_lang::prelude::borsh::BorshSerialize)]
             pub struct #name {
                 #(#account_struct_fields),*
             }
@@ -254,9 +297,20 @@ pub fn generate(accs: AccountsStruct) -> proc_macro2::TokenStream {
         impl#combined_generics anchor_lang::Accounts#trait_generics for #name#strct_generics {
             #[inline(never)]
             fn try_accounts(program_id: &anchor_lang::solana_program::pubkey::Pubkey, accounts: &mut &[anchor_lang::solana_program::account_info::AccountInfo<'info>]) -> std::result::Result<Self, anchor_lang::solana_program::program_error::ProgramError> {
+                let mut bumps = std::collections::BTreeMap::new();
+                Self::try_accounts_with_bumps(program_id, accounts, &mut bumps)
+            }
+
+            #idl_build
+
+            #[inline(never)]
+            fn try_accounts_with_bumps(program_id: &anchor_lang::solana_program::pubkey::Pubkey, accounts: &mut &[anchor_lang::solana_program::account_info::AccountInfo<'info>], bumps: &mut std::collections::BTreeMap<String, u8>) -> std::result::Result<Self, anchor_lang::solana_program::program_error::ProgramError> {
                 // Deserialize each account.
                 #(#deser_fields)*
 
+                // Create any accounts paid for by the program.
+                #(#init_fields)*
+
                 // Perform constraint checks on each account.
                 #(#access_checks)*
 
@@ -297,24 +351,57 @@ pub fn generate(accs: AccountsStruct) -> proc_macro2::TokenStream {
     }
 }
 
+// Returns the path to the client accounts struct generated for a composite
+// field, within the same module as the composite struct's definition.
+fn generate_client_accounts_path(f: &CompositeField) -> proc_macro2::TokenStream {
+    let path = match &f.raw_field.ty {
+        syn::Type::Path(ty_path) => &ty_path.path,
+        ty => return syn::Error::new_spanned(ty, "invalid account type").to_compile_error(),
+    };
+    let module: Vec<&syn::Ident> = path
+        .segments
+        .iter()
+        .take(path.segments.len() - 1)
+        .map(|segment| &segment.ident)
+        .collect();
+    let client_mod = proc_macro2::Ident::new(
+        &format!("__client_accounts_{}", f.symbol.to_snake_case()),
+        proc_macro2::Span::call_site(),
+    );
+    let symbol = proc_macro2::Ident::new(&f.symbol, proc_macro2::Span::call_site());
+    quote! {
+        #(#module::)*#client_mod::#symbol
+    }
+}
+
 pub fn generate_field_constraint(f: &Field, c: &Constraint) -> proc_macro2::TokenStream {
     match c {
         Constraint::BelongsTo(c) => generate_constraint_belongs_to(f, c),
         Constraint::Signer(c) => generate_constraint_signer(f, c),
-        Constraint::Literal(c) => generate_constraint_literal(c),
+        Constraint::Literal(c) => generate_constraint_literal(&f.ident, c),
         Constraint::Owner(c) => generate_constraint_owner(f, c),
         Constraint::RentExempt(c) => generate_constraint_rent_exempt(f, c),
-        Constraint::Seeds(c) => generate_constraint_seeds(f, c),
+        Constraint::Seeds(c) => match f.init_constraint() {
+            None => generate_constraint_seeds(f, c),
+            // Checked upon account creation, since the seeds sign for it.
+            Some(_) => quote! {},
+        },
+        // Handled upon account creation, before any constraints are checked.
+        Constraint::Init(_) => quote! {},
+        Constraint::Close(c) => generate_constraint_close(f, c),
     }
 }
 
 pub fn generate_composite_constraint(
-    _f: &CompositeField,
+    f: &CompositeField,
     c: &Constraint,
 ) -> proc_macro2::TokenStream {
     match c {
-        Constraint::Literal(c) => generate_constraint_literal(c),
-        _ => panic!("Composite fields can only use literal constraints"),
+        Constraint::Literal(c) => generate_constraint_literal(&f.ident, c),
+        _ => invalid_constraint(
+            &f.ident,
+            "composite fields can only use literal constraints",
+        ),
     }
 }
 
@@ -324,9 +411,14 @@ pub fn generate_constraint_belongs_to(
 ) -> proc_macro2::TokenStream {
     let target = c.join_target.clone();
     let ident = &f.ident;
+    let error = generate_constraint_error(ident, "ConstraintBelongsTo");
+    let field = match f.ty {
+        Ty::ZeroCopyAccount(_) => quote! { #ident.load()?.#target },
+        _ => quote! { #ident.#target },
+    };
     quote! {
-        if &#ident.#target != #target.to_account_info().key {
-            return Err(anchor_lang::solana_program::program_error::ProgramError::Custom(1)); // todo: error codes
+        if &#field != #target.to_account_info().key {
+            #error
         }
     }
 }
@@ -336,8 +428,11 @@ pub fn generate_constraint_signer(f: &Field, _c: &ConstraintSigner) -> proc_macr
     let info = match f.ty {
         Ty::AccountInfo => quote! { #ident },
         Ty::ProgramAccount(_) => quote! { #ident.to_account_info() },
-        _ => panic!("Invalid syntax: signer cannot be specified."),
+        Ty::ZeroCopyAccount(_) => quote! { #ident.to_account_info() },
+        Ty::Signer => quote! { #ident.to_account_info() },
+        _ => return invalid_constraint(ident, "signer cannot be specified"),
     };
+    let error = generate_constraint_error(ident, "ConstraintSigner");
     quote! {
         // Don't enforce on CPI, since usually a program is signing and so
         // the `try_accounts` deserializatoin will fail *if* the one
@@ -346,17 +441,21 @@ pub fn generate_constraint_signer(f: &Field, _c: &ConstraintSigner) -> proc_macr
         // This check will be performed on the other end of the invocation.
         if cfg!(not(feature = "cpi")) {
             if !#info.is_signer {
-                return Err(anchor_lang::solana_program::program_error::ProgramError::MissingRequiredSignature);
+                #error
             }
         }
     }
 }
 
-pub fn generate_constraint_literal(c: &ConstraintLiteral) -> proc_macro2::TokenStream {
+pub fn generate_constraint_literal(
+    ident: &syn::Ident,
+    c: &ConstraintLiteral,
+) -> proc_macro2::TokenStream {
     let tokens = &c.tokens;
+    let error = generate_constraint_error(ident, "ConstraintLiteral");
     quote! {
         if !(#tokens) {
-            return Err(anchor_lang::solana_program::program_error::ProgramError::Custom(1)); // todo: error codes
+            #error
         }
     }
 }
@@ -366,13 +465,15 @@ pub fn generate_constraint_owner(f: &Field, c: &ConstraintOwner) -> proc_macro2:
     let info = match f.ty {
         Ty::AccountInfo => quote! { #ident },
         Ty::ProgramAccount(_) => quote! { #ident.to_account_info() },
-        _ => panic!("Invalid syntax: owner cannot be specified."),
+        Ty::ZeroCopyAccount(_) => quote! { #ident.to_account_info() },
+        _ => return invalid_constraint(ident, "owner cannot be specified"),
     };
+    let error = generate_constraint_error(ident, "ConstraintOwner");
     match c {
         ConstraintOwner::Skip => quote! {},
         ConstraintOwner::Program => quote! {
             if #info.owner != program_id {
-                return Err(anchor_lang::solana_program::program_error::ProgramError::Custom(1)); // todo: error codes
+                #error
             }
         },
     }
@@ -386,13 +487,15 @@ pub fn generate_constraint_rent_exempt(
     let info = match f.ty {
         Ty::AccountInfo => quote! { #ident },
         Ty::ProgramAccount(_) => quote! { #ident.to_account_info() },
-        _ => panic!("Invalid syntax: rent exemption cannot be specified."),
+        Ty::ZeroCopyAccount(_) => quote! { #ident.to_account_info() },
+        _ => return invalid_constraint(ident, "rent exemption cannot be specified"),
     };
+    let error = generate_constraint_error(ident, "ConstraintRentExempt");
     match c {
         ConstraintRentExempt::Skip => quote! {},
         ConstraintRentExempt::Enforce => quote! {
             if !rent.is_exempt(#info.lamports(), #info.try_data_len()?) {
-                return Err(anchor_lang::solana_program::program_error::ProgramError::Custom(2)); // todo: error codes
+                #error
             }
         },
     }
@@ -401,13 +504,166 @@ pub fn generate_constraint_rent_exempt(
 pub fn generate_constraint_seeds(f: &Field, c: &ConstraintSeeds) -> proc_macro2::TokenStream {
     let name = &f.ident;
     let seeds = &c.seeds;
+    let error = generate_constraint_error(name, "ConstraintSeeds");
+    match c.bump {
+        false => quote! {
+            let program_signer = match anchor_lang::solana_program::pubkey::Pubkey::create_program_address(
+                &#seeds,
+                program_id,
+            ) {
+                Ok(program_signer) => program_signer,
+                Err(_) => {
+                    #error
+                }
+            };
+            if #name.to_account_info().key != &program_signer {
+                #error
+            }
+        },
+        true => {
+            let name_str = name.to_string();
+            quote! {
+                let (program_signer, bump) = anchor_lang::solana_program::pubkey::Pubkey::find_program_address(
+                    &#seeds,
+                    program_id,
+                );
+                if #name.to_account_info().key != &program_signer {
+                    #error
+                }
+                bumps.insert(#name_str.to_string(), bump);
+            }
+        }
+    }
+}
+
+// The account is closed on exit, so make sure its lamports don't go back to
+// itself.
+pub fn generate_constraint_close(f: &Field, c: &ConstraintClose) -> proc_macro2::TokenStream {
+    let name = &f.ident;
+    let sol_dest = &c.sol_dest;
+    let error = generate_constraint_error(name, "ConstraintClose");
+    quote! {
+        if #name.to_account_info().key == #sol_dest.to_account_info().key {
+            #error
+        }
+    }
+}
+
+// Logs the account field violating a constraint and returns the framework
+// error code reserved for the constraint.
+// The parser rejects constraints that don't apply to a field's type, but
+// fields constructed by hand are reported rather than panicking.
+fn invalid_constraint(ident: &syn::Ident, msg: &str) -> proc_macro2::TokenStream {
+    syn::Error::new_spanned(ident, msg).to_compile_error()
+}
+
+fn generate_constraint_error(ident: &syn::Ident, error_code: &str) -> proc_macro2::TokenStream {
+    let error_code = proc_macro2::Ident::new(error_code, proc_macro2::Span::call_site());
     quote! {
-        let program_signer = Pubkey::create_program_address(
-            &#seeds,
-            program_id,
-        ).map_err(|_| anchor_lang::solana_program::program_error::ProgramError::Custom(1))?; // todo
-        if #name.to_account_info().key != &program_signer {
-            return Err(anchor_lang::solana_program::program_error::ProgramError::Custom(1)); // todo
+        anchor_lang::solana_program::msg!(
+            "{}: {}",
+            anchor_lang::ErrorCode::#error_code.msg(),
+            stringify!(#ident),
+        );
+        return Err(anchor_lang::ErrorCode::#error_code.into());
+    }
+}
+
+// Creates the account with the system program, funded by the `payer` field,
+// and then deserializes it. Expects `rent` and `system_program` fields to be
+// in the accounts struct.
+//
+// If the account is a program derived address, i.e., has a seeds constraint,
+// the seeds are checked and then used to sign for the account's creation.
+pub fn generate_init(f: &Field, c: &ConstraintInit) -> proc_macro2::TokenStream {
+    let name = &f.ident;
+    let typed_name = f.typed_ident();
+    let payer = &c.payer;
+    let space = &c.space;
+    let (seeds_check, signer_seeds) = match f.seeds_constraint() {
+        None => (quote! {}, quote! { &[] }),
+        Some(seeds) => {
+            let seeds_check = generate_constraint_seeds(f, seeds);
+            let signer_seeds = match seeds.bump {
+                false => {
+                    let seeds = &seeds.seeds;
+                    quote! { &[&#seeds[..]] }
+                }
+                true => {
+                    // Append the bump to the user given seeds.
+                    let mut seeds: Vec<proc_macro2::TokenTree> =
+                        seeds.seeds.stream().into_iter().collect();
+                    if let Some(proc_macro2::TokenTree::Punct(punct)) = seeds.last() {
+                        if punct.as_char() == ',' {
+                            seeds.pop();
+                        }
+                    }
+                    quote! { &[&[#(#seeds)*, &[bump][..]][..]] }
+                }
+            };
+            (seeds_check, signer_seeds)
         }
+    };
+    quote! {
+        let #typed_name = {
+            #seeds_check
+            let space: usize = #space;
+            let lamports = rent.minimum_balance(space);
+            let current_lamports = #name.lamports();
+            if current_lamports == 0 {
+                anchor_lang::solana_program::program::invoke_signed(
+                    &anchor_lang::solana_program::system_instruction::create_account(
+                        #payer.to_account_info().key,
+                        #name.key,
+                        lamports,
+                        space as u64,
+                        program_id,
+                    ),
+                    &[
+                        #payer.to_account_info(),
+                        #name.clone(),
+                        system_program.to_account_info(),
+                    ],
+                    #signer_seeds,
+                )?;
+            } else {
+                // The system program refuses to create an account holding
+                // lamports, e.g., sent to the address ahead of time, so top it
+                // up to rent exemption, then allocate and assign it instead.
+                let required_lamports = lamports.saturating_sub(current_lamports);
+                if required_lamports > 0 {
+                    anchor_lang::solana_program::program::invoke(
+                        &anchor_lang::solana_program::system_instruction::transfer(
+                            #payer.to_account_info().key,
+                            #name.key,
+                            required_lamports,
+                        ),
+                        &[
+                            #payer.to_account_info(),
+                            #name.clone(),
+                            system_program.to_account_info(),
+                        ],
+                    )?;
+                }
+                anchor_lang::solana_program::program::invoke_signed(
+                    &anchor_lang::solana_program::system_instruction::allocate(
+                        #name.key,
+                        space as u64,
+                    ),
+                    &[#name.clone(), system_program.to_account_info()],
+                    #signer_seeds,
+                )?;
+                anchor_lang::solana_program::program::invoke_signed(
+                    &anchor_lang::solana_program::system_instruction::assign(
+                        #name.key,
+                        program_id,
+                    ),
+                    &[#name.clone(), system_program.to_account_info()],
+                    #signer_seeds,
+                )?;
+            }
+            let mut accounts: &[anchor_lang::solana_program::account_info::AccountInfo] = &[#name];
+            anchor_lang::AccountsInit::try_accounts_init(program_id, &mut accounts)?
+        };
     }
 }
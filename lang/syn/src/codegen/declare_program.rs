@@ -0,0 +1,631 @@
+//! Client code generated from a program's IDL by `declare_program!`, for
+//! talking to programs whose crate isn't available as a dependency. The
+//! generated module mirrors what the `cpi` feature of a program crate exposes:
+//! the `instruction` and `accounts` modules, account structs, types, events and
+//! the error enum.
+
+use crate::codegen::program::{sighash, SIGHASH_GLOBAL_NAMESPACE, SIGHASH_STATE_NAMESPACE};
+use crate::idl::{
+    EnumFields, Idl, IdlAccountItem, IdlErrorCode, IdlEvent, IdlField, IdlIx, IdlType, IdlTypeDef,
+    IdlTypeDefTy,
+};
+use heck::{CamelCase, SnakeCase};
+use quote::{format_ident, quote};
+
+// Errors 0-100 are reserved for the framework.
+const ERROR_CODE_OFFSET: u32 = 100;
+
+pub fn generate(idl: &Idl) -> proc_macro2::TokenStream {
+    let mod_name = format_ident!("{}", idl.name.to_snake_case());
+    let doc = format!(
+        " Client for the `{}` program, generated from its IDL.",
+        idl.name
+    );
+    let address = idl
+        .metadata
+        .as_ref()
+        .and_then(|m| m.get("address"))
+        .and_then(|a| a.as_str());
+
+    let id = match address {
+        None => quote! {},
+        Some(address) => generate_id(idl, address),
+    };
+    let types: Vec<proc_macro2::TokenStream> = idl.types.iter().map(generate_ty_def).collect();
+    let accounts: Vec<proc_macro2::TokenStream> = idl
+        .accounts
+        .iter()
+        .map(|acc| generate_account(acc, address.is_some()))
+        .collect();
+    let events: Vec<proc_macro2::TokenStream> =
+        idl.events.iter().flatten().map(generate_event).collect();
+    let ixs = generate_ixs(idl);
+    let ix_accounts = generate_ix_accounts(idl);
+    let errors = generate_errors(idl.errors.as_deref().unwrap_or_default());
+
+    quote! {
+        #[doc = #doc]
+        pub mod #mod_name {
+            use anchor_lang::prelude::borsh;
+
+            #id
+
+            #(#types)*
+
+            #(#accounts)*
+
+            #(#events)*
+
+            #ixs
+
+            #ix_accounts
+
+            #errors
+        }
+    }
+}
+
+fn generate_id(idl: &Idl, address: &str) -> proc_macro2::TokenStream {
+    let bytes = match bs58::decode(address).into_vec() {
+        Ok(bytes) if bytes.len() == 32 => bytes,
+        _ => {
+            return syn::Error::new(
+                proc_macro2::Span::call_site(),
+                format!("invalid program address in the IDL: {}", address),
+            )
+            .to_compile_error()
+        }
+    };
+    let program_name = format_ident!("{}Program", idl.name.to_camel_case());
+    quote! {
+        /// The program's id, from the IDL's metadata.
+        pub static ID: anchor_lang::solana_program::pubkey::Pubkey =
+            anchor_lang::solana_program::pubkey::Pubkey::new_from_array([#(#bytes,)*]);
+
+        /// Returns the program ID.
+        pub fn id() -> anchor_lang::solana_program::pubkey::Pubkey {
+            ID
+        }
+
+        /// The program, for use as a `Program<'info, T>` account.
+        #[derive(Clone)]
+        pub struct #program_name;
+
+        impl anchor_lang::Id for #program_name {
+            fn id() -> anchor_lang::solana_program::pubkey::Pubkey {
+                ID
+            }
+        }
+    }
+}
+
+fn generate_ty_def(ty_def: &IdlTypeDef) -> proc_macro2::TokenStream {
+    let name = format_ident!("{}", ty_def.name);
+    let docs = generate_docs(&ty_def.docs);
+    let body = match &ty_def.ty {
+        IdlTypeDefTy::Struct { generics, fields } => {
+            let generics = generate_generics(generics);
+            let fields = generate_fields(fields, true);
+            quote! {
+                pub struct #name #generics {
+                    #(#fields),*
+                }
+            }
+        }
+        IdlTypeDefTy::Tuple { generics, fields } => {
+            let generics = generate_generics(generics);
+            let fields: Vec<proc_macro2::TokenStream> = fields.iter().map(generate_ty).collect();
+            quote! {
+                pub struct #name #generics(#(pub #fields),*);
+            }
+        }
+        IdlTypeDefTy::Enum { generics, variants } => {
+            let generics = generate_generics(generics);
+            let variants: Vec<proc_macro2::TokenStream> = variants
+                .iter()
+                .map(|variant| {
+                    let name = format_ident!("{}", variant.name);
+                    match &variant.fields {
+                        None => quote! { #name },
+                        Some(EnumFields::Named(fields)) => {
+                            let fields = generate_fields(fields, false);
+                            quote! { #name { #(#fields),* } }
+                        }
+                        Some(EnumFields::Tuple(fields)) => {
+                            let fields: Vec<proc_macro2::TokenStream> =
+                                fields.iter().map(generate_ty).collect();
+                            quote! { #name(#(#fields),*) }
+                        }
+                    }
+                })
+                .collect();
+            quote! {
+                pub enum #name #generics {
+                    #(#variants),*
+                }
+            }
+        }
+    };
+    quote! {
+        #docs
+        #[derive(anchor_lang::prelude::borsh::BorshSerialize, anchor_lang::prelude::borsh::BorshDeserialize, Clone, Debug)]
+        #body
+    }
+}
+
+// Accounts are the same as any other type, prefixed by the discriminator of
+// the `#[account]` attribute.
+fn generate_account(acc: &IdlTypeDef, has_id: bool) -> proc_macro2::TokenStream {
+    let name = format_ident!("{}", acc.name);
+    let discriminator = discriminator(&format!("account:{}", acc.name));
+    let owner = match has_id {
+        false => quote! { None },
+        true => quote! { Some(ID) },
+    };
+    if acc.zero_copy {
+        return generate_zero_copy_account(acc, discriminator, owner);
+    }
+    let ty_def = generate_ty_def(acc);
+    quote! {
+        #ty_def
+
+        impl anchor_lang::AccountSerialize for #name {
+            fn try_serialize<W: std::io::Write>(&self, writer: &mut W) -> std::result::Result<(), anchor_lang::solana_program::program_error::ProgramError> {
+                writer.write_all(&#discriminator).map_err(|_| anchor_lang::solana_program::program_error::ProgramError::InvalidAccountData)?;
+                anchor_lang::AnchorSerialize::serialize(self, writer)
+                    .map_err(|_| anchor_lang::solana_program::program_error::ProgramError::InvalidAccountData)?;
+                Ok(())
+            }
+        }
+
+        impl anchor_lang::AccountDeserialize for #name {
+            fn try_deserialize(buf: &mut &[u8]) -> std::result::Result<Self, anchor_lang::solana_program::program_error::ProgramError> {
+                if buf.len() < #discriminator.len() {
+                    return Err(anchor_lang::solana_program::program_error::ProgramError::AccountDataTooSmall);
+                }
+                let given_disc = &buf[..8];
+                if &#discriminator != given_disc {
+                    return Err(anchor_lang::solana_program::program_error::ProgramError::InvalidInstructionData);
+                }
+                Self::try_deserialize_unchecked(buf)
+            }
+
+            fn try_deserialize_unchecked(buf: &mut &[u8]) -> std::result::Result<Self, anchor_lang::solana_program::program_error::ProgramError> {
+                let mut data: &[u8] = &buf[8..];
+                anchor_lang::AnchorDeserialize::deserialize(&mut data)
+                    .map_err(|_| anchor_lang::solana_program::program_error::ProgramError::InvalidAccountData)
+            }
+        }
+
+        impl anchor_lang::Discriminator for #name {
+            const DISCRIMINATOR: [u8; 8] = #discriminator;
+        }
+
+        impl anchor_lang::Owner for #name {
+            fn owner() -> Option<anchor_lang::solana_program::pubkey::Pubkey> {
+                #owner
+            }
+        }
+    }
+}
+
+// Zero copy accounts are cast from the account data rather than deserialized,
+// as with `#[account(zero_copy)]`, so the struct has the same `#[repr(C)]`
+// layout and implements `ZeroCopy`.
+fn generate_zero_copy_account(
+    acc: &IdlTypeDef,
+    discriminator: proc_macro2::TokenStream,
+    owner: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let name = format_ident!("{}", acc.name);
+    let fields = match &acc.ty {
+        IdlTypeDefTy::Struct { generics, fields } if generics.is_empty() => fields,
+        _ => {
+            return syn::Error::new(
+                proc_macro2::Span::call_site(),
+                format!(
+                    "zero copy account {} must be a struct without generics",
+                    acc.name
+                ),
+            )
+            .to_compile_error()
+        }
+    };
+    let docs = generate_docs(&acc.docs);
+    let field_tys: Vec<proc_macro2::TokenStream> =
+        fields.iter().map(|f| generate_ty(&f.ty)).collect();
+    let fields = generate_fields(fields, true);
+    quote! {
+        #docs
+        #[derive(Clone, Copy, Debug)]
+        #[repr(C)]
+        pub struct #name {
+            #(#fields),*
+        }
+
+        const _: fn() = || {
+            fn assert_pod<T: anchor_lang::bytemuck::Pod>() {}
+            #(assert_pod::<#field_tys>();)*
+        };
+        const _: [(); 0] = [(); std::mem::size_of::<#name>()
+            - (0 #(+ std::mem::size_of::<#field_tys>())*)];
+
+        unsafe impl anchor_lang::bytemuck::Pod for #name {}
+        unsafe impl anchor_lang::bytemuck::Zeroable for #name {}
+
+        impl anchor_lang::ZeroCopy for #name {}
+
+        impl anchor_lang::AccountSerialize for #name {
+            fn try_serialize<W: std::io::Write>(&self, writer: &mut W) -> std::result::Result<(), anchor_lang::solana_program::program_error::ProgramError> {
+                writer.write_all(&#discriminator).map_err(|_| anchor_lang::solana_program::program_error::ProgramError::InvalidAccountData)?;
+                writer.write_all(anchor_lang::bytemuck::bytes_of(self)).map_err(|_| anchor_lang::solana_program::program_error::ProgramError::InvalidAccountData)?;
+                Ok(())
+            }
+        }
+
+        impl anchor_lang::AccountDeserialize for #name {
+            fn try_deserialize(buf: &mut &[u8]) -> std::result::Result<Self, anchor_lang::solana_program::program_error::ProgramError> {
+                if buf.len() < #discriminator.len() {
+                    return Err(anchor_lang::solana_program::program_error::ProgramError::AccountDataTooSmall);
+                }
+                let given_disc = &buf[..8];
+                if &#discriminator != given_disc {
+                    return Err(anchor_lang::solana_program::program_error::ProgramError::InvalidInstructionData);
+                }
+                Self::try_deserialize_unchecked(buf)
+            }
+
+            fn try_deserialize_unchecked(buf: &mut &[u8]) -> std::result::Result<Self, anchor_lang::solana_program::program_error::ProgramError> {
+                let data = buf
+                    .get(8..8 + std::mem::size_of::<Self>())
+                    .ok_or(anchor_lang::solana_program::program_error::ProgramError::AccountDataTooSmall)?;
+                // The data isn't necessarily aligned for `Self`, so it's copied
+                // rather than cast.
+                let mut account = <Self as anchor_lang::bytemuck::Zeroable>::zeroed();
+                anchor_lang::bytemuck::bytes_of_mut(&mut account).copy_from_slice(data);
+                Ok(account)
+            }
+        }
+
+        impl anchor_lang::Discriminator for #name {
+            const DISCRIMINATOR: [u8; 8] = #discriminator;
+        }
+
+        impl anchor_lang::Owner for #name {
+            fn owner() -> Option<anchor_lang::solana_program::pubkey::Pubkey> {
+                #owner
+            }
+        }
+    }
+}
+
+fn generate_event(event: &IdlEvent) -> proc_macro2::TokenStream {
+    let name = format_ident!("{}", event.name);
+    let fields = generate_fields(&event.fields, true);
+    let discriminator = discriminator(&format!("event:{}", event.name));
+    quote! {
+        #[derive(anchor_lang::prelude::borsh::BorshSerialize, anchor_lang::prelude::borsh::BorshDeserialize, Clone, Debug)]
+        pub struct #name {
+            #(#fields),*
+        }
+
+        impl anchor_lang::Event for #name {
+            fn data(&self) -> Vec<u8> {
+                let mut d = #discriminator.to_vec();
+                d.append(&mut anchor_lang::AnchorSerialize::try_to_vec(self).unwrap());
+                d
+            }
+        }
+
+        impl anchor_lang::Discriminator for #name {
+            const DISCRIMINATOR: [u8; 8] = #discriminator;
+        }
+    }
+}
+
+// Instructions are named as in the `instruction` module generated by
+// `#[program]`, with state methods prefixed by `__` and the constructor
+// named `__Ctor`.
+fn ix_names(idl: &Idl) -> Vec<(proc_macro2::Ident, [u8; 8], &IdlIx)> {
+    let state_methods = idl.state.iter().flat_map(|state| &state.methods);
+    state_methods
+        .map(|method| match method.name.as_str() {
+            "new" => (
+                format_ident!("__Ctor"),
+                sighash(SIGHASH_STATE_NAMESPACE, "new"),
+                method,
+            ),
+            name => (
+                format_ident!("__{}", name.to_camel_case()),
+                sighash(SIGHASH_STATE_NAMESPACE, &name.to_snake_case()),
+                method,
+            ),
+        })
+        .chain(idl.instructions.iter().map(|ix| {
+            (
+                format_ident!("{}", ix.name.to_camel_case()),
+                sighash(SIGHASH_GLOBAL_NAMESPACE, &ix.name.to_snake_case()),
+                ix,
+            )
+        }))
+        .collect()
+}
+
+fn generate_ixs(idl: &Idl) -> proc_macro2::TokenStream {
+    let ixs: Vec<proc_macro2::TokenStream> = ix_names(idl)
+        .into_iter()
+        .map(|(name, sighash, ix)| {
+            let docs = generate_docs(&ix.docs);
+            let sighash: proc_macro2::TokenStream = format!("{:?}", sighash).parse().unwrap();
+            let strct = match ix.args.is_empty() {
+                true => quote! {
+                    pub struct #name;
+                },
+                false => {
+                    let args = generate_fields(&ix.args, true);
+                    quote! {
+                        pub struct #name {
+                            #(#args),*
+                        }
+                    }
+                }
+            };
+            quote! {
+                #docs
+                #[derive(anchor_lang::prelude::borsh::BorshSerialize, anchor_lang::prelude::borsh::BorshDeserialize)]
+                #strct
+
+                impl anchor_lang::InstructionData for #name {
+                    fn data(&self) -> Vec<u8> {
+                        let mut d = #sighash.to_vec();
+                        d.append(&mut anchor_lang::AnchorSerialize::try_to_vec(self).expect("Should always serialize"));
+                        d
+                    }
+                }
+            }
+        })
+        .collect();
+    quote! {
+        /// Instruction data of each of the program's instructions.
+        pub mod instruction {
+            use super::*;
+
+            #(#ixs)*
+        }
+    }
+}
+
+fn generate_ix_accounts(idl: &Idl) -> proc_macro2::TokenStream {
+    let mut strcts = vec![];
+    for (name, _sighash, ix) in ix_names(idl) {
+        generate_accounts_struct(&name, &ix.accounts, &mut strcts);
+    }
+    quote! {
+        /// Accounts of each of the program's instructions, named after the
+        /// instruction, where each field is a `Pubkey`.
+        pub mod accounts {
+            use super::*;
+
+            #(#strcts)*
+        }
+    }
+}
+
+// Composite accounts become their own struct, named after the parent struct
+// and the field.
+fn generate_accounts_struct(
+    name: &proc_macro2::Ident,
+    items: &[IdlAccountItem],
+    strcts: &mut Vec<proc_macro2::TokenStream>,
+) {
+    let mut fields = vec![];
+    let mut metas = vec![];
+    for item in items {
+        match item {
+            IdlAccountItem::IdlAccounts(accs) => {
+                let field = format_ident!("{}", accs.name.to_snake_case());
+                let ty = format_ident!("{}{}", name, accs.name.to_camel_case());
+                generate_accounts_struct(&ty, &accs.accounts, strcts);
+                fields.push(quote! { pub #field: #ty });
+                metas.push(quote! {
+                    account_metas.extend(anchor_lang::ToAccountMetas::to_account_metas(&self.#field, is_signer));
+                });
+            }
+            IdlAccountItem::IdlAccount(acc) => {
+                let field = format_ident!("{}", acc.name.to_snake_case());
+                let docs = generate_docs(&acc.docs);
+                let is_signer = acc.is_signer;
+                let meta = match acc.is_mut {
+                    false => {
+                        quote! { anchor_lang::solana_program::instruction::AccountMeta::new_readonly }
+                    }
+                    true => quote! { anchor_lang::solana_program::instruction::AccountMeta::new },
+                };
+                fields.push(quote! {
+                    #docs
+                    pub #field: anchor_lang::solana_program::pubkey::Pubkey
+                });
+                metas.push(quote! {
+                    account_metas.push(#meta(self.#field, is_signer.unwrap_or(#is_signer)));
+                });
+            }
+        }
+    }
+    strcts.push(quote! {
+        #[derive(Clone, Debug)]
+        pub struct #name {
+            #(#fields),*
+        }
+
+        impl anchor_lang::ToAccountMetas for #name {
+            fn to_account_metas(&self, is_signer: Option<bool>) -> Vec<anchor_lang::solana_program::instruction::AccountMeta> {
+                let mut account_metas = vec![];
+
+                #(#metas)*
+
+                account_metas
+            }
+        }
+    });
+}
+
+// Only the user defined errors, since the framework's are known to clients.
+fn generate_errors(errors: &[IdlErrorCode]) -> proc_macro2::TokenStream {
+    let errors: Vec<&IdlErrorCode> = errors
+        .iter()
+        .filter(|e| e.code >= ERROR_CODE_OFFSET)
+        .collect();
+    if errors.is_empty() {
+        return quote! {};
+    }
+    let variants: Vec<proc_macro2::TokenStream> = errors
+        .iter()
+        .map(|e| {
+            let name = format_ident!("{}", e.name);
+            let docs = generate_docs(&e.docs);
+            let discriminant = e.code - ERROR_CODE_OFFSET;
+            quote! {
+                #docs
+                #name = #discriminant
+            }
+        })
+        .collect();
+    let from_code_arms: Vec<proc_macro2::TokenStream> = errors
+        .iter()
+        .map(|e| {
+            let name = format_ident!("{}", e.name);
+            let code = e.code;
+            quote! { #code => Some(ErrorCode::#name) }
+        })
+        .collect();
+    let msg_arms: Vec<proc_macro2::TokenStream> = errors
+        .iter()
+        .map(|e| {
+            let name = format_ident!("{}", e.name);
+            let msg = e.msg.clone().unwrap_or_else(|| e.name.clone());
+            quote! { ErrorCode::#name => #msg }
+        })
+        .collect();
+    quote! {
+        /// Errors returned by the program, as `ProgramError::Custom` codes
+        /// offset by 100.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        #[repr(u32)]
+        pub enum ErrorCode {
+            #(#variants),*
+        }
+
+        impl ErrorCode {
+            /// Returns the error with the given `ProgramError::Custom` code.
+            pub fn from_code(code: u32) -> Option<Self> {
+                match code {
+                    #(#from_code_arms,)*
+                    _ => None,
+                }
+            }
+        }
+
+        impl std::fmt::Display for ErrorCode {
+            fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
+                let msg = match self {
+                    #(#msg_arms),*
+                };
+                fmt.write_str(msg)
+            }
+        }
+
+        impl std::error::Error for ErrorCode {}
+
+        impl std::convert::From<ErrorCode> for anchor_lang::solana_program::program_error::ProgramError {
+            fn from(e: ErrorCode) -> anchor_lang::solana_program::program_error::ProgramError {
+                anchor_lang::solana_program::program_error::ProgramError::Custom(e as u32 + #ERROR_CODE_OFFSET)
+            }
+        }
+    }
+}
+
+fn generate_fields(fields: &[IdlField], is_pub: bool) -> Vec<proc_macro2::TokenStream> {
+    fields
+        .iter()
+        .map(|f| {
+            let name = format_ident!("{}", f.name.to_snake_case());
+            let ty = generate_ty(&f.ty);
+            let docs = generate_docs(&f.docs);
+            match is_pub {
+                true => quote! { #docs pub #name: #ty },
+                false => quote! { #docs #name: #ty },
+            }
+        })
+        .collect()
+}
+
+fn generate_generics(generics: &[String]) -> proc_macro2::TokenStream {
+    if generics.is_empty() {
+        return quote! {};
+    }
+    let generics: Vec<proc_macro2::Ident> =
+        generics.iter().map(|g| format_ident!("{}", g)).collect();
+    quote! { <#(#generics),*> }
+}
+
+fn generate_docs(docs: &Option<Vec<String>>) -> proc_macro2::TokenStream {
+    let docs = docs
+        .iter()
+        .flatten()
+        .map(|line| format!(" {}", line))
+        .collect::<Vec<String>>();
+    quote! { #(#[doc = #docs])* }
+}
+
+fn generate_ty(ty: &IdlType) -> proc_macro2::TokenStream {
+    match ty {
+        IdlType::Bool => quote! { bool },
+        IdlType::U8 => quote! { u8 },
+        IdlType::I8 => quote! { i8 },
+        IdlType::U16 => quote! { u16 },
+        IdlType::I16 => quote! { i16 },
+        IdlType::U32 => quote! { u32 },
+        IdlType::I32 => quote! { i32 },
+        IdlType::U64 => quote! { u64 },
+        IdlType::I64 => quote! { i64 },
+        IdlType::U128 => quote! { u128 },
+        IdlType::I128 => quote! { i128 },
+        IdlType::F32 => quote! { f32 },
+        IdlType::F64 => quote! { f64 },
+        IdlType::Bytes => quote! { Vec<u8> },
+        IdlType::String => quote! { String },
+        IdlType::PublicKey => quote! { anchor_lang::solana_program::pubkey::Pubkey },
+        IdlType::Defined(name) | IdlType::Generic(name) => {
+            let name = format_ident!("{}", name);
+            quote! { #name }
+        }
+        IdlType::DefinedWithGenerics { name, generics } => {
+            let name = format_ident!("{}", name);
+            let generics: Vec<proc_macro2::TokenStream> =
+                generics.iter().map(generate_ty).collect();
+            quote! { #name<#(#generics),*> }
+        }
+        IdlType::Option(ty) => {
+            let ty = generate_ty(ty);
+            quote! { Option<#ty> }
+        }
+        IdlType::Vec(ty) => {
+            let ty = generate_ty(ty);
+            quote! { Vec<#ty> }
+        }
+        IdlType::Array(ty, len) => {
+            let ty = generate_ty(ty);
+            quote! { [#ty; #len] }
+        }
+        IdlType::Tuple(tys) => {
+            let tys: Vec<proc_macro2::TokenStream> = tys.iter().map(generate_ty).collect();
+            quote! { (#(#tys,)*) }
+        }
+    }
+}
+
+fn discriminator(preimage: &str) -> proc_macro2::TokenStream {
+    let mut discriminator = [0u8; 8];
+    discriminator.copy_from_slice(&crate::hash::hash(preimage.as_bytes()).to_bytes()[..8]);
+    format!("{:?}", discriminator).parse().unwrap()
+}
@@ -1,7 +1,12 @@
-use crate::Error;
-use quote::quote;
+use crate::{Error, FRAMEWORK_ERROR_CODES};
+use quote::{format_ident, quote};
 
 pub fn generate(error: Error) -> proc_macro2::TokenStream {
+    #[cfg(feature = "idl-build")]
+    let idl_build = crate::codegen::idl_build::generate_error(&error);
+    #[cfg(not(feature = "idl-build"))]
+    let idl_build = quote! {};
+
     let error_enum = error.raw_enum;
     let enum_name = &error.ident;
     quote! {
@@ -44,5 +49,49 @@ pub fn generate(error: Error) -> proc_macro2::TokenStream {
                 err.into()
             }
         }
+
+        #idl_build
+    }
+}
+
+// Generates `anchor_lang::ErrorCode` from the framework's reserved codes, so
+// that the codes returned by programs always match those in the IDL.
+pub fn generate_framework_error_code() -> proc_macro2::TokenStream {
+    let variants: Vec<proc_macro2::TokenStream> = FRAMEWORK_ERROR_CODES
+        .iter()
+        .map(|(code, name, msg)| {
+            let name = format_ident!("{}", name);
+            let code = proc_macro2::Literal::u32_unsuffixed(*code);
+            quote! {
+                #[doc = #msg]
+                #name = #code
+            }
+        })
+        .collect();
+    let msgs: Vec<proc_macro2::TokenStream> = FRAMEWORK_ERROR_CODES
+        .iter()
+        .map(|(_code, name, msg)| {
+            let name = format_ident!("{}", name);
+            quote! {
+                ErrorCode::#name => #msg
+            }
+        })
+        .collect();
+    quote! {
+        /// Error codes reserved by the framework. Codes 0-100 belong to Anchor,
+        /// and user defined errors are offset by 100.
+        #[derive(Debug, Clone, Copy)]
+        #[repr(u32)]
+        pub enum ErrorCode {
+            #(#variants,)*
+        }
+
+        impl ErrorCode {
+            pub fn msg(&self) -> &'static str {
+                match self {
+                    #(#msgs,)*
+                }
+            }
+        }
     }
 }
@@ -0,0 +1,198 @@
+//! IDL fragments emitted by each macro when the `idl-build` feature is
+//! enabled. Every fragment is printed by a generated test, so that
+//! `anchor build` can collect the IDL from the output of
+//! `cargo test --features idl-build`, rather than by re-parsing the source.
+
+use crate::idl::{IdlAccountItem, IdlIx, IdlState, IdlTypeDef, IDL_FRAGMENT_PREFIX};
+use crate::parser::file as idl_parser;
+use crate::{AccountField, AccountsStruct, Error, Program};
+use heck::{MixedCase, SnakeCase};
+use quote::{format_ident, quote};
+
+// The test printing a fragment known at compile time.
+fn generate_print_fn(fn_name: &str, kind: &str, json: String) -> proc_macro2::TokenStream {
+    let fn_name = format_ident!("__anchor_private_print_idl_{}", fn_name);
+    quote! {
+        #[cfg(test)]
+        #[test]
+        fn #fn_name() {
+            println!("{} {} {}", #IDL_FRAGMENT_PREFIX, #kind, #json);
+        }
+    }
+}
+
+// Returns the `Accounts::__anchor_private_idl_accounts` method of the struct,
+// calling into the method of each composite field's type. Hand-written
+// `Accounts` implementations fall back to the trait's default, without any
+// accounts.
+pub fn generate_accounts(accs: &AccountsStruct) -> proc_macro2::TokenStream {
+    let items: Vec<proc_macro2::TokenStream> = accs
+        .fields
+        .iter()
+        .map(|f: &AccountField| match f {
+            AccountField::AccountsStruct(comp_f) => {
+                let name = comp_f.ident.to_string().to_mixed_case();
+                let ty = &comp_f.raw_field.ty;
+                quote! {
+                    anchor_lang::anchor_syn::idl::IdlAccountItem::IdlAccounts(
+                        anchor_lang::anchor_syn::idl::IdlAccounts {
+                            name: #name.to_string(),
+                            accounts: <#ty as anchor_lang::Accounts<'_>>::__anchor_private_idl_accounts(),
+                        }
+                    )
+                }
+            }
+            AccountField::Field(f) => {
+                let json =
+                    serde_json::to_string(&IdlAccountItem::IdlAccount(f.idl_account())).unwrap();
+                quote! {
+                    anchor_lang::serde_json::from_str(#json).unwrap()
+                }
+            }
+        })
+        .collect();
+    quote! {
+        fn __anchor_private_idl_accounts() -> Vec<anchor_lang::anchor_syn::idl::IdlAccountItem> {
+            vec![#(#items),*]
+        }
+    }
+}
+
+// Prints the program's instructions and state, filling in the accounts of
+// each from its `Accounts` struct at runtime.
+pub fn generate_program(program: &Program) -> proc_macro2::TokenStream {
+    let name = program.name.to_string();
+    let no_accounts = |_: &syn::Ident| vec![];
+    let state: Option<IdlState> = idl_parser::parse_state(program, no_accounts);
+    let state_json = serde_json::to_string(&state).unwrap();
+    let state_anchors: Vec<&syn::Ident> = match &program.state {
+        None => vec![],
+        Some(state) => state
+            .ctor_and_anchor
+            .iter()
+            .map(|(_ctor, anchor_ident)| anchor_ident)
+            .chain(
+                state
+                    .impl_block_and_methods
+                    .iter()
+                    .flat_map(|(_impl_block, methods)| methods)
+                    .map(|method| &method.anchor_ident),
+            )
+            .collect(),
+    };
+    let ixs: Vec<IdlIx> = idl_parser::parse_ixs(program, no_accounts);
+    let ixs_json = serde_json::to_string(&ixs).unwrap();
+    let ix_anchors: Vec<&syn::Ident> = program.ixs.iter().map(|ix| &ix.anchor_ident).collect();
+
+    quote! {
+        #[cfg(test)]
+        #[test]
+        fn __anchor_private_print_idl_program() {
+            let mut state: Option<anchor_lang::anchor_syn::idl::IdlState> =
+                anchor_lang::serde_json::from_str(#state_json).unwrap();
+            if let Some(state) = state.as_mut() {
+                let accounts = vec![#(<#state_anchors as anchor_lang::Accounts>::__anchor_private_idl_accounts()),*];
+                for (method, accounts) in state.methods.iter_mut().zip(accounts) {
+                    method.accounts = accounts;
+                }
+            }
+            let mut instructions: Vec<anchor_lang::anchor_syn::idl::IdlIx> =
+                anchor_lang::serde_json::from_str(#ixs_json).unwrap();
+            let accounts = vec![#(<#ix_anchors as anchor_lang::Accounts>::__anchor_private_idl_accounts()),*];
+            for (ix, accounts) in instructions.iter_mut().zip(accounts) {
+                ix.accounts = accounts;
+            }
+            let idl = anchor_lang::anchor_syn::idl::Idl {
+                version: "0.0.0".to_string(),
+                name: #name.to_string(),
+                instructions,
+                state,
+                accounts: vec![],
+                types: vec![],
+                events: None,
+                errors: None,
+                metadata: None,
+            };
+            println!(
+                "{} program {}",
+                #IDL_FRAGMENT_PREFIX,
+                anchor_lang::serde_json::to_string(&idl).unwrap(),
+            );
+        }
+    }
+}
+
+// The account attribute isn't given its own attribute, so whether the account
+// is zero copy is passed in.
+pub fn generate_account(
+    account_strct: &syn::ItemStruct,
+    zero_copy: bool,
+) -> proc_macro2::TokenStream {
+    match idl_parser::parse_struct_ty_def(account_strct) {
+        Ok(ty_def) => generate_print_fn(
+            &format!(
+                "account_{}",
+                account_strct.ident.to_string().to_snake_case()
+            ),
+            "account",
+            serde_json::to_string(&IdlTypeDef {
+                zero_copy,
+                ..ty_def
+            })
+            .unwrap(),
+        ),
+        Err(e) => syn::Error::new_spanned(account_strct, e).to_compile_error(),
+    }
+}
+
+// Prints the definition of a type deriving `AnchorSerialize` or
+// `AnchorDeserialize`, named after the `derive`, since types deriving both are
+// printed twice. As when parsing the source, only public structs are exported.
+// Structs generated by the framework derive borsh's traits directly instead, so
+// that they aren't exported.
+pub fn generate_type(item: &syn::Item, derive: &str) -> proc_macro2::TokenStream {
+    let ty_def = match item {
+        syn::Item::Struct(item_strct) => match &item_strct.vis {
+            syn::Visibility::Public(_) => idl_parser::parse_struct_ty_def(item_strct),
+            _ => return quote! {},
+        },
+        syn::Item::Enum(enm) => idl_parser::parse_enum_ty_def(enm),
+        _ => return quote! {},
+    };
+    match ty_def {
+        Ok(ty_def) => generate_print_fn(
+            &format!("type_{}_{}", ty_def.name.to_snake_case(), derive),
+            "type",
+            serde_json::to_string(&ty_def).unwrap(),
+        ),
+        Err(e) => syn::Error::new_spanned(item, e).to_compile_error(),
+    }
+}
+
+pub fn generate_event(event_strct: &syn::ItemStruct) -> proc_macro2::TokenStream {
+    match idl_parser::parse_event(event_strct) {
+        Ok(event) => generate_print_fn(
+            &format!("event_{}", event_strct.ident.to_string().to_snake_case()),
+            "event",
+            serde_json::to_string(&event).unwrap(),
+        ),
+        Err(e) => syn::Error::new_spanned(event_strct, e).to_compile_error(),
+    }
+}
+
+// Prints the user defined error codes. The framework's are added by the CLI.
+pub fn generate_error(error: &Error) -> proc_macro2::TokenStream {
+    generate_print_fn(
+        "errors",
+        "errors",
+        serde_json::to_string(&idl_parser::idl_user_error_codes(error)).unwrap(),
+    )
+}
+
+pub fn generate_address(id: &syn::LitStr) -> proc_macro2::TokenStream {
+    generate_print_fn(
+        "address",
+        "address",
+        serde_json::to_string(&id.value()).unwrap(),
+    )
+}
@@ -1,3 +1,7 @@
 pub mod accounts;
+#[cfg(feature = "idl")]
+pub mod declare_program;
 pub mod error;
+#[cfg(feature = "idl-build")]
+pub mod idl_build;
 pub mod program;
@@ -4,14 +4,13 @@ use heck::{CamelCase, SnakeCase};
 use quote::quote;
 
 // Namespace for calculating state instruction sighash signatures.
-const SIGHASH_STATE_NAMESPACE: &str = "state";
+pub const SIGHASH_STATE_NAMESPACE: &str = "state";
 
 // Namespace for calculating instruction sighash signatures for any instruction
 // not affecting program state.
-const SIGHASH_GLOBAL_NAMESPACE: &str = "global";
+pub const SIGHASH_GLOBAL_NAMESPACE: &str = "global";
 
 pub fn generate(program: Program) -> proc_macro2::TokenStream {
-    let mod_name = &program.name;
     let dispatch = generate_dispatch(&program);
     let handlers_non_inlined = generate_non_inlined_handlers(&program);
     let methods = generate_methods(&program);
@@ -19,14 +18,22 @@ pub fn generate(program: Program) -> proc_macro2::TokenStream {
     let cpi = generate_cpi(&program);
     let accounts = generate_accounts(&program);
 
-    quote! {
-        // TODO: remove once we allow segmented paths in `Accounts` structs.
-        use #mod_name::*;
+    #[cfg(feature = "idl-build")]
+    let idl_build = crate::codegen::idl_build::generate_program(&program);
+    #[cfg(not(feature = "idl-build"))]
+    let idl_build = quote! {};
 
+    quote! {
         #[cfg(not(feature = "no-entrypoint"))]
         anchor_lang::solana_program::entrypoint!(entry);
         #[cfg(not(feature = "no-entrypoint"))]
         fn entry(program_id: &Pubkey, accounts: &[AccountInfo], ix_data: &[u8]) -> ProgramResult {
+            if *program_id != crate::ID {
+                anchor_lang::solana_program::msg!(
+                    anchor_lang::ErrorCode::DeclaredProgramIdMismatch.msg()
+                );
+                return Err(anchor_lang::ErrorCode::DeclaredProgramIdMismatch.into());
+            }
             if ix_data.len() < 8 {
                 return Err(ProgramError::Custom(99));
             }
@@ -62,6 +69,8 @@ pub fn generate(program: Program) -> proc_macro2::TokenStream {
         #methods
 
         #cpi
+
+        #idl_build
     }
 }
 
@@ -159,12 +168,12 @@ pub fn generate_dispatch(program: &Program) -> proc_macro2::TokenStream {
                                 let args_struct = {
                                     if m.args.is_empty() {
                                         quote! {
-                                            #[derive(anchor_lang::AnchorSerialize, anchor_lang::AnchorDeserialize)]
+                                            #[derive(anchor_lang::prelude::borsh::BorshSerialize, anchor_lang::prelude::borsh::BorshDeserialize)]
                                             struct Args;
                                         }
                                     } else {
                                         quote! {
-                                            #[derive(anchor_lang::AnchorSerialize, anchor_lang::AnchorDeserialize)]
+                                            #[derive(anchor_lang::prelude::borsh::BorshSerialize, anchor_lang::prelude::borsh::BorshDeserialize)]
                                             struct Args {
                                                 #(#raw_args),*
                                             }
@@ -387,7 +396,8 @@ pub fn generate_non_inlined_handlers(program: &Program) -> proc_macro2::TokenStr
 
                         // Deserialize accounts.
                         let ctor_accounts = anchor_lang::Ctor::try_accounts(program_id, &mut remaining_accounts)?;
-                        let mut ctor_user_def_accounts = #anchor_ident::try_accounts(program_id, &mut remaining_accounts)?;
+                        let mut bumps = std::collections::BTreeMap::new();
+                        let mut ctor_user_def_accounts = #anchor_ident::try_accounts_with_bumps(program_id, &mut remaining_accounts, &mut bumps)?;
 
                         // Invoke the ctor.
                         let instance = #mod_name::#name::new(
@@ -395,6 +405,7 @@ pub fn generate_non_inlined_handlers(program: &Program) -> proc_macro2::TokenStr
                                 program_id,
                                 &mut ctor_user_def_accounts,
                                 remaining_accounts,
+                                bumps,
                             ),
                             #(#ctor_untyped_args),*
                         )?;
@@ -476,7 +487,7 @@ pub fn generate_non_inlined_handlers(program: &Program) -> proc_macro2::TokenStr
 
                                 // Deserialize the program state account.
                                 let state_account = &remaining_accounts[0];
-                                let mut state: #state_ty = {
+                                let mut state: #program_name::#state_ty = {
                                     let data = state_account.try_borrow_data()?;
                                     let mut sliced: &[u8] = &data;
                                     anchor_lang::AccountDeserialize::try_deserialize(&mut sliced)?
@@ -485,11 +496,13 @@ pub fn generate_non_inlined_handlers(program: &Program) -> proc_macro2::TokenStr
                                 remaining_accounts = &remaining_accounts[1..];
 
                                 // Deserialize the program's execution context.
-                                let mut accounts = #anchor_ident::try_accounts(
+                                let mut bumps = std::collections::BTreeMap::new();
+                                let mut accounts = #anchor_ident::try_accounts_with_bumps(
                                     program_id,
                                     &mut remaining_accounts,
+                                    &mut bumps,
                                 )?;
-                                let ctx = Context::new(program_id, &mut accounts, remaining_accounts);
+                                let ctx = Context::new(program_id, &mut accounts, remaining_accounts, bumps);
 
                                 // Execute user defined function.
                                 state.#ix_name(
@@ -552,7 +565,7 @@ pub fn generate_non_inlined_handlers(program: &Program) -> proc_macro2::TokenStr
 
                                             // Deserialize the program state account.
                                             let state_account = &remaining_accounts[0];
-                                            let mut state: #state_ty = {
+                                            let mut state: #program_name::#state_ty = {
                                                 let data = state_account.try_borrow_data()?;
                                                 let mut sliced: &[u8] = &data;
                                                 anchor_lang::AccountDeserialize::try_deserialize(&mut sliced)?
@@ -561,11 +574,13 @@ pub fn generate_non_inlined_handlers(program: &Program) -> proc_macro2::TokenStr
                                             remaining_accounts = &remaining_accounts[1..];
 
                                             // Deserialize the program's execution context.
-                                            let mut accounts = #anchor_ident::try_accounts(
+                                            let mut bumps = std::collections::BTreeMap::new();
+                                            let mut accounts = #anchor_ident::try_accounts_with_bumps(
                                                 program_id,
                                                 &mut remaining_accounts,
+                                                &mut bumps,
                                             )?;
-                                            let ctx = Context::new(program_id, &mut accounts, remaining_accounts);
+                                            let ctx = Context::new(program_id, &mut accounts, remaining_accounts, bumps);
 
                                             // Execute user defined function.
                                             state.#ix_name(
@@ -593,12 +608,14 @@ pub fn generate_non_inlined_handlers(program: &Program) -> proc_macro2::TokenStr
                                             #(#ix_params),*
                                         ) -> ProgramResult {
                                             let mut remaining_accounts: &[AccountInfo] = accounts;
-                                            let mut accounts = #anchor_ident::try_accounts(
+                                            let mut bumps = std::collections::BTreeMap::new();
+                                            let mut accounts = #anchor_ident::try_accounts_with_bumps(
                                                 program_id,
                                                 &mut remaining_accounts,
+                                                &mut bumps,
                                             )?;
-                                            #state_name::#ix_name(
-                                                Context::new(program_id, &mut accounts, remaining_accounts),
+                                            #program_name::#state_name::#ix_name(
+                                                Context::new(program_id, &mut accounts, remaining_accounts, bumps),
                                                 #(#ix_arg_names),*
                                             )?;
                                             accounts.exit(program_id)
@@ -629,9 +646,10 @@ pub fn generate_non_inlined_handlers(program: &Program) -> proc_macro2::TokenStr
                     #(#ix_params),*
                 ) -> ProgramResult {
                     let mut remaining_accounts: &[AccountInfo] = accounts;
-                    let mut accounts = #anchor::try_accounts(program_id, &mut remaining_accounts)?;
+                    let mut bumps = std::collections::BTreeMap::new();
+                    let mut accounts = #anchor::try_accounts_with_bumps(program_id, &mut remaining_accounts, &mut bumps)?;
                     #program_name::#ix_name(
-                        Context::new(program_id, &mut accounts, remaining_accounts),
+                        Context::new(program_id, &mut accounts, remaining_accounts, bumps),
                         #(#ix_arg_names),*
                     )?;
                     accounts.exit(program_id)
@@ -683,12 +701,12 @@ pub fn generate_ctor_typed_variant_with_semi(program: &Program) -> proc_macro2::
                 .collect();
             if ctor_args.is_empty() {
                 quote! {
-                    #[derive(AnchorSerialize, AnchorDeserialize)]
+                    #[derive(anchor_lang::prelude::borsh::BorshSerialize, anchor_lang::prelude::borsh::BorshDeserialize)]
                     pub struct __Ctor;
                 }
             } else {
                 quote! {
-                    #[derive(AnchorSerialize, AnchorDeserialize)]
+                    #[derive(anchor_lang::prelude::borsh::BorshSerialize, anchor_lang::prelude::borsh::BorshDeserialize)]
                     pub struct __Ctor {
                         #(#ctor_args),*
                     }
@@ -837,14 +855,14 @@ pub fn generate_ixs(program: &Program) -> proc_macro2::TokenStream {
                         // If no args, output a "unit" variant instead of a struct variant.
                         if method.args.is_empty() {
                             quote! {
-                                #[derive(AnchorSerialize, AnchorDeserialize)]
+                                #[derive(anchor_lang::prelude::borsh::BorshSerialize, anchor_lang::prelude::borsh::BorshDeserialize)]
                                 pub struct #ix_name_camel;
 
                                 #ix_data_trait
                             }
                         } else {
                             quote! {
-                                #[derive(AnchorSerialize, AnchorDeserialize)]
+                                #[derive(anchor_lang::prelude::borsh::BorshSerialize, anchor_lang::prelude::borsh::BorshDeserialize)]
                                 pub struct #ix_name_camel {
                                     #(#raw_args),*
                                 }
@@ -890,14 +908,14 @@ pub fn generate_ixs(program: &Program) -> proc_macro2::TokenStream {
             // If no args, output a "unit" variant instead of a struct variant.
             if ix.args.is_empty() {
                 quote! {
-                    #[derive(AnchorSerialize, AnchorDeserialize)]
+                    #[derive(anchor_lang::prelude::borsh::BorshSerialize, anchor_lang::prelude::borsh::BorshDeserialize)]
                     pub struct #ix_name_camel;
 
                     #ix_data_trait
                 }
             } else {
                 quote! {
-                    #[derive(AnchorSerialize, AnchorDeserialize)]
+                    #[derive(anchor_lang::prelude::borsh::BorshSerialize, anchor_lang::prelude::borsh::BorshDeserialize)]
                     pub struct #ix_name_camel {
                         #(#raw_args),*
                     }
@@ -996,6 +1014,12 @@ fn generate_cpi(program: &Program) -> proc_macro2::TokenStream {
                         ctx: CpiContext<'a, 'b, 'c, 'info, #accounts_ident<'info>>,
                         #(#args),*
                     ) -> ProgramResult {
+                        if ctx.program.key != &crate::ID {
+                            anchor_lang::solana_program::msg!(
+                                anchor_lang::ErrorCode::InvalidProgramId.msg()
+                            );
+                            return Err(anchor_lang::ErrorCode::InvalidProgramId.into());
+                        }
                         let ix = {
                             let ix = instruction::#ix_variant;
                             let mut ix_data = AnchorSerialize::try_to_vec(&ix)
@@ -0,0 +1,928 @@
+//! Borsh encoding and decoding of data described by an IDL to and from JSON,
+//! for tools using a program's accounts and instructions without access to
+//! its crate.
+//!
+//! Integers up to 64 bits become JSON numbers, while 128 bit integers, which
+//! JSON can't represent, become decimal strings. Public keys are base58
+//! strings and enums are externally tagged, i.e., `"Variant"` for unit
+//! variants and `{"Variant": ..}` otherwise. Encoding accepts the same
+//! representations, as well as decimal strings for any integer.
+
+use super::{
+    EnumFields, Idl, IdlAccount, IdlAccountItem, IdlField, IdlIx, IdlType, IdlTypeDef, IdlTypeDefTy,
+};
+use crate::codegen::program::{sighash, SIGHASH_GLOBAL_NAMESPACE, SIGHASH_STATE_NAMESPACE};
+use anyhow::{anyhow, Result};
+use heck::SnakeCase;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::convert::{TryFrom, TryInto};
+
+// The first 8 bytes of the instructions managing the program's IDL account,
+// i.e., `anchor_lang::idl::IDL_IX_TAG`.
+const IDL_IX_TAG: u64 = 0x0a69e9a778bcf440;
+
+// Decodes a little endian primitive, advancing `data` past it.
+macro_rules! decode_le {
+    ($data:expr, $ty:ty) => {{
+        let bytes = take($data, std::mem::size_of::<$ty>())?;
+        <$ty>::from_le_bytes(bytes.try_into().unwrap())
+    }};
+}
+
+/// Decodes the data of the account with the given name, checking its
+/// discriminator as computed by the `#[account]` attribute.
+pub fn decode_account(idl: &Idl, name: &str, data: &[u8]) -> Result<Value> {
+    let ty_def = find_ty_def(idl, name)?;
+    if data.len() < 8 {
+        return Err(anyhow!("Account data too small"));
+    }
+    if data[..8] != account_discriminator(name) {
+        return Err(anyhow!("Account discriminator doesn't match {}", name));
+    }
+    if ty_def.zero_copy {
+        check_zero_copy(idl, ty_def)?;
+    }
+    // Accounts may be allocated more space than their data takes up, so any
+    // trailing bytes are ignored.
+    decode_ty_def(idl, ty_def, &mut &data[8..])
+}
+
+// Zero copy accounts are laid out as `#[repr(C)]` structs without padding,
+// which is the same as their Borsh encoding only if every field is a fixed
+// size `Pod` type, i.e., not a bool, option, vec, string, tuple or enum.
+fn check_zero_copy(idl: &Idl, ty_def: &IdlTypeDef) -> Result<()> {
+    check_zero_copy_fields(idl, ty_def, &mut vec![])
+}
+
+// `parents` are the names of the types containing `ty_def`, which can't
+// contain themselves, as they'd have no fixed size.
+fn check_zero_copy_fields<'a>(
+    idl: &'a Idl,
+    ty_def: &'a IdlTypeDef,
+    parents: &mut Vec<&'a str>,
+) -> Result<()> {
+    let fields = match &ty_def.ty {
+        IdlTypeDefTy::Struct { fields, .. } => fields,
+        _ => return Err(anyhow!("Zero copy type {} must be a struct", ty_def.name)),
+    };
+    if parents.contains(&ty_def.name.as_str()) {
+        return Err(anyhow!("Zero copy type {} contains itself", ty_def.name));
+    }
+    parents.push(&ty_def.name);
+    for f in fields {
+        if !is_pod(idl, &f.ty, parents)? {
+            return Err(anyhow!(
+                "Unable to decode field {} of zero copy type {}",
+                f.name,
+                ty_def.name
+            ));
+        }
+    }
+    parents.pop();
+    Ok(())
+}
+
+fn is_pod<'a>(idl: &'a Idl, ty: &'a IdlType, parents: &mut Vec<&'a str>) -> Result<bool> {
+    Ok(match ty {
+        IdlType::U8
+        | IdlType::I8
+        | IdlType::U16
+        | IdlType::I16
+        | IdlType::U32
+        | IdlType::I32
+        | IdlType::U64
+        | IdlType::I64
+        | IdlType::U128
+        | IdlType::I128
+        | IdlType::F32
+        | IdlType::F64
+        | IdlType::PublicKey => true,
+        IdlType::Array(ty, _) => is_pod(idl, ty, parents)?,
+        IdlType::Defined(name) => {
+            check_zero_copy_fields(idl, find_ty_def(idl, name)?, parents)?;
+            true
+        }
+        _ => false,
+    })
+}
+
+/// Returns the discriminator prefixing the data of the account with the given
+/// name.
+pub fn account_discriminator(name: &str) -> [u8; 8] {
+    let mut discriminator = [0u8; 8];
+    discriminator.copy_from_slice(
+        &crate::hash::hash(format!("account:{}", name).as_bytes()).to_bytes()[..8],
+    );
+    discriminator
+}
+
+/// An instruction decoded with the program's IDL.
+#[derive(Debug, Clone, Serialize)]
+pub struct DecodedInstruction {
+    pub name: String,
+    pub args: Value,
+    /// Names of the instruction's accounts, in order. Accounts of composite
+    /// structs are prefixed by the struct's field, e.g., `inner.vault`.
+    pub accounts: Vec<String>,
+}
+
+/// Decodes instruction data, matching its 8 byte sighash against the
+/// program's instructions, its state methods and the instructions managing
+/// its IDL account.
+pub fn decode_instruction(idl: &Idl, data: &[u8]) -> Result<DecodedInstruction> {
+    if data.len() < 8 {
+        return Err(anyhow!("Instruction data too small"));
+    }
+    let (ix_sighash, mut data) = data.split_at(8);
+    if ix_sighash == IDL_IX_TAG.to_le_bytes() {
+        return decode_idl_instruction(idl, &mut data);
+    }
+    let state_methods = idl
+        .state
+        .iter()
+        .flat_map(|state| &state.methods)
+        .map(|method| (SIGHASH_STATE_NAMESPACE, method));
+    let ixs = idl
+        .instructions
+        .iter()
+        .map(|ix| (SIGHASH_GLOBAL_NAMESPACE, ix));
+    let ix: &IdlIx = state_methods
+        .chain(ixs)
+        .find(|(namespace, ix)| ix_sighash == sighash(namespace, &ix.name.to_snake_case()))
+        .map(|(_namespace, ix)| ix)
+        .ok_or_else(|| anyhow!("Unknown instruction sighash {:?}", ix_sighash))?;
+    Ok(DecodedInstruction {
+        name: ix.name.clone(),
+        args: decode_fields(idl, &ix.args, &mut data)?,
+        accounts: flatten_accounts(&ix.accounts)
+            .into_iter()
+            .map(|(name, _acc)| name)
+            .collect(),
+    })
+}
+
+// Instructions generated by `#[program]` to create and write the program's
+// IDL account, with data `anchor_lang::idl::IdlInstruction`.
+fn decode_idl_instruction(idl: &Idl, data: &mut &[u8]) -> Result<DecodedInstruction> {
+    let field = |name: &str, ty: IdlType| IdlField {
+        name: name.to_string(),
+        ty,
+        docs: None,
+    };
+    let (name, fields, accounts) = match decode_le!(data, u8) {
+        0 => (
+            "idlCreate",
+            vec![field("dataLen", IdlType::U64)],
+            vec!["from", "to", "base", "systemProgram", "program", "rent"],
+        ),
+        1 => (
+            "idlWrite",
+            vec![field("data", IdlType::Bytes)],
+            vec!["idl", "authority"],
+        ),
+        2 => ("idlClear", vec![], vec!["idl", "authority"]),
+        3 => (
+            "idlSetAuthority",
+            vec![field("newAuthority", IdlType::PublicKey)],
+            vec!["idl", "authority"],
+        ),
+        tag => return Err(anyhow!("Invalid IDL instruction: {}", tag)),
+    };
+    Ok(DecodedInstruction {
+        name: name.to_string(),
+        args: decode_fields(idl, &fields, data)?,
+        accounts: accounts.into_iter().map(ToString::to_string).collect(),
+    })
+}
+
+/// Returns the accounts, in order, named by their path through any
+/// composite structs, e.g., `inner.vault`.
+pub fn flatten_accounts(items: &[IdlAccountItem]) -> Vec<(String, &IdlAccount)> {
+    flatten_accounts_with_prefix("", items)
+}
+
+fn flatten_accounts_with_prefix<'a>(
+    prefix: &str,
+    items: &'a [IdlAccountItem],
+) -> Vec<(String, &'a IdlAccount)> {
+    items
+        .iter()
+        .flat_map(|item| match item {
+            IdlAccountItem::IdlAccount(acc) => vec![(format!("{}{}", prefix, acc.name), acc)],
+            IdlAccountItem::IdlAccounts(accs) => {
+                flatten_accounts_with_prefix(&format!("{}{}.", prefix, accs.name), &accs.accounts)
+            }
+        })
+        .collect()
+}
+
+/// Finds the instruction or state method with the given name, given either
+/// as in the IDL or in snake case.
+pub fn find_instruction<'a>(idl: &'a Idl, name: &str) -> Result<&'a IdlIx> {
+    find_ix(idl, name).map(|(_namespace, ix)| ix)
+}
+
+// Instructions take precedence over state methods of the same name, as
+// with the dispatch generated by `#[program]`.
+fn find_ix<'a>(idl: &'a Idl, name: &str) -> Result<(&'static str, &'a IdlIx)> {
+    let name = name.to_snake_case();
+    let ixs = idl
+        .instructions
+        .iter()
+        .map(|ix| (SIGHASH_GLOBAL_NAMESPACE, ix));
+    let state_methods = idl
+        .state
+        .iter()
+        .flat_map(|state| &state.methods)
+        .map(|method| (SIGHASH_STATE_NAMESPACE, method));
+    ixs.chain(state_methods)
+        .find(|(_namespace, ix)| ix.name.to_snake_case() == name)
+        .ok_or_else(|| anyhow!("Instruction not found: {}", name))
+}
+
+/// Encodes the arguments of the instruction with the given name, prefixed by
+/// its sighash, returning the instruction found along with its data.
+pub fn encode_instruction<'a>(
+    idl: &'a Idl,
+    name: &str,
+    args: &Value,
+) -> Result<(&'a IdlIx, Vec<u8>)> {
+    let (namespace, ix) = find_ix(idl, name)?;
+    let mut data = sighash(namespace, &ix.name.to_snake_case()).to_vec();
+    encode_fields(idl, &ix.args, args, &mut data)?;
+    Ok((ix, data))
+}
+
+/// Decodes the fields, in order, into an object keyed by field name.
+pub fn decode_fields(idl: &Idl, fields: &[IdlField], data: &mut &[u8]) -> Result<Value> {
+    let mut obj = Map::new();
+    for f in fields {
+        let value = decode(idl, &f.ty, data)
+            .map_err(|e| anyhow!("Unable to decode field {}: {}", f.name, e))?;
+        obj.insert(f.name.clone(), value);
+    }
+    Ok(Value::Object(obj))
+}
+
+/// Decodes a value of the given type, advancing `data` past it.
+pub fn decode(idl: &Idl, ty: &IdlType, data: &mut &[u8]) -> Result<Value> {
+    let value = match ty {
+        IdlType::Bool => match decode_le!(data, u8) {
+            0 => Value::Bool(false),
+            1 => Value::Bool(true),
+            b => return Err(anyhow!("Invalid bool: {}", b)),
+        },
+        IdlType::U8 => decode_le!(data, u8).into(),
+        IdlType::I8 => decode_le!(data, i8).into(),
+        IdlType::U16 => decode_le!(data, u16).into(),
+        IdlType::I16 => decode_le!(data, i16).into(),
+        IdlType::U32 => decode_le!(data, u32).into(),
+        IdlType::I32 => decode_le!(data, i32).into(),
+        IdlType::U64 => decode_le!(data, u64).into(),
+        IdlType::I64 => decode_le!(data, i64).into(),
+        IdlType::U128 => Value::String(decode_le!(data, u128).to_string()),
+        IdlType::I128 => Value::String(decode_le!(data, i128).to_string()),
+        IdlType::F32 => decode_le!(data, f32).into(),
+        IdlType::F64 => decode_le!(data, f64).into(),
+        IdlType::Bytes => {
+            let len = decode_len(data)?;
+            take(data, len)?.to_vec().into()
+        }
+        IdlType::String => {
+            let len = decode_len(data)?;
+            String::from_utf8(take(data, len)?.to_vec())?.into()
+        }
+        IdlType::PublicKey => Value::String(bs58::encode(take(data, 32)?).into_string()),
+        IdlType::Defined(name) => decode_ty_def(idl, find_ty_def(idl, name)?, data)?,
+        IdlType::DefinedWithGenerics { name, generics } => {
+            let ty_def = instantiate(find_ty_def(idl, name)?, generics)?;
+            decode_ty_def(idl, &ty_def, data)?
+        }
+        IdlType::Generic(name) => return Err(anyhow!("Uninstantiated generic type {}", name)),
+        IdlType::Option(ty) => match decode_le!(data, u8) {
+            0 => Value::Null,
+            1 => decode(idl, ty, data)?,
+            b => return Err(anyhow!("Invalid option tag: {}", b)),
+        },
+        IdlType::Vec(ty) => {
+            let len = decode_len(data)?;
+            Value::Array(
+                (0..len)
+                    .map(|_| decode(idl, ty, data))
+                    .collect::<Result<_>>()?,
+            )
+        }
+        IdlType::Array(ty, len) => Value::Array(
+            (0..*len)
+                .map(|_| decode(idl, ty, data))
+                .collect::<Result<_>>()?,
+        ),
+        IdlType::Tuple(tys) => decode_tuple(idl, tys, data)?,
+    };
+    Ok(value)
+}
+
+fn decode_ty_def(idl: &Idl, ty_def: &IdlTypeDef, data: &mut &[u8]) -> Result<Value> {
+    match &ty_def.ty {
+        IdlTypeDefTy::Struct { fields, .. } => decode_fields(idl, fields, data),
+        IdlTypeDefTy::Tuple { fields, .. } => decode_tuple(idl, fields, data),
+        IdlTypeDefTy::Enum { variants, .. } => {
+            let tag = decode_le!(data, u8) as usize;
+            let variant = variants
+                .get(tag)
+                .ok_or_else(|| anyhow!("Invalid variant {} of {}", tag, ty_def.name))?;
+            let value = match &variant.fields {
+                None => return Ok(Value::String(variant.name.clone())),
+                Some(EnumFields::Named(fields)) => decode_fields(idl, fields, data)?,
+                Some(EnumFields::Tuple(tys)) => decode_tuple(idl, tys, data)?,
+            };
+            let mut obj = Map::new();
+            obj.insert(variant.name.clone(), value);
+            Ok(Value::Object(obj))
+        }
+    }
+}
+
+fn decode_tuple(idl: &Idl, tys: &[IdlType], data: &mut &[u8]) -> Result<Value> {
+    Ok(Value::Array(
+        tys.iter()
+            .map(|ty| decode(idl, ty, data))
+            .collect::<Result<_>>()?,
+    ))
+}
+
+// Length prefix of dynamically sized types.
+fn decode_len(data: &mut &[u8]) -> Result<usize> {
+    Ok(decode_le!(data, u32) as usize)
+}
+
+fn take<'a>(data: &mut &'a [u8], len: usize) -> Result<&'a [u8]> {
+    if data.len() < len {
+        return Err(anyhow!("Unexpected end of data"));
+    }
+    let (bytes, rest) = data.split_at(len);
+    *data = rest;
+    Ok(bytes)
+}
+
+/// Encodes an object keyed by field name into the fields, in order.
+pub fn encode_fields(
+    idl: &Idl,
+    fields: &[IdlField],
+    value: &Value,
+    data: &mut Vec<u8>,
+) -> Result<()> {
+    let obj = value
+        .as_object()
+        .ok_or_else(|| anyhow!("Expected an object, found {}", value))?;
+    for f in fields {
+        let value = obj
+            .get(&f.name)
+            .ok_or_else(|| anyhow!("Missing field {}", f.name))?;
+        encode(idl, &f.ty, value, data)
+            .map_err(|e| anyhow!("Unable to encode field {}: {}", f.name, e))?;
+    }
+    Ok(())
+}
+
+/// Encodes a value of the given type, appending it to `data`.
+pub fn encode(idl: &Idl, ty: &IdlType, value: &Value, data: &mut Vec<u8>) -> Result<()> {
+    match ty {
+        IdlType::Bool => {
+            let b = value
+                .as_bool()
+                .ok_or_else(|| anyhow!("Expected a bool, found {}", value))?;
+            data.push(b as u8);
+        }
+        IdlType::U8 => data.extend_from_slice(&parse_int::<u8>(value)?.to_le_bytes()),
+        IdlType::I8 => data.extend_from_slice(&parse_int::<i8>(value)?.to_le_bytes()),
+        IdlType::U16 => data.extend_from_slice(&parse_int::<u16>(value)?.to_le_bytes()),
+        IdlType::I16 => data.extend_from_slice(&parse_int::<i16>(value)?.to_le_bytes()),
+        IdlType::U32 => data.extend_from_slice(&parse_int::<u32>(value)?.to_le_bytes()),
+        IdlType::I32 => data.extend_from_slice(&parse_int::<i32>(value)?.to_le_bytes()),
+        IdlType::U64 => data.extend_from_slice(&parse_int::<u64>(value)?.to_le_bytes()),
+        IdlType::I64 => data.extend_from_slice(&parse_int::<i64>(value)?.to_le_bytes()),
+        IdlType::U128 => data.extend_from_slice(&parse_int::<u128>(value)?.to_le_bytes()),
+        IdlType::I128 => data.extend_from_slice(&parse_int::<i128>(value)?.to_le_bytes()),
+        IdlType::F32 => data.extend_from_slice(&(parse_float(value)? as f32).to_le_bytes()),
+        IdlType::F64 => data.extend_from_slice(&parse_float(value)?.to_le_bytes()),
+        IdlType::Bytes => {
+            let bytes = as_array(value)?
+                .iter()
+                .map(parse_int::<u8>)
+                .collect::<Result<Vec<u8>>>()?;
+            encode_len(bytes.len(), data)?;
+            data.extend_from_slice(&bytes);
+        }
+        IdlType::String => {
+            let s = value
+                .as_str()
+                .ok_or_else(|| anyhow!("Expected a string, found {}", value))?;
+            encode_len(s.len(), data)?;
+            data.extend_from_slice(s.as_bytes());
+        }
+        IdlType::PublicKey => {
+            let s = value
+                .as_str()
+                .ok_or_else(|| anyhow!("Expected a public key, found {}", value))?;
+            let key = bs58::decode(s).into_vec()?;
+            if key.len() != 32 {
+                return Err(anyhow!("Invalid public key: {}", s));
+            }
+            data.extend_from_slice(&key);
+        }
+        IdlType::Defined(name) => encode_ty_def(idl, find_ty_def(idl, name)?, value, data)?,
+        IdlType::DefinedWithGenerics { name, generics } => {
+            let ty_def = instantiate(find_ty_def(idl, name)?, generics)?;
+            encode_ty_def(idl, &ty_def, value, data)?;
+        }
+        IdlType::Generic(name) => return Err(anyhow!("Uninstantiated generic type {}", name)),
+        IdlType::Option(ty) => match value {
+            Value::Null => data.push(0),
+            _ => {
+                data.push(1);
+                encode(idl, ty, value, data)?;
+            }
+        },
+        IdlType::Vec(ty) => {
+            let values = as_array(value)?;
+            encode_len(values.len(), data)?;
+            for value in values {
+                encode(idl, ty, value, data)?;
+            }
+        }
+        IdlType::Array(ty, len) => {
+            let values = as_array(value)?;
+            if values.len() != *len {
+                return Err(anyhow!(
+                    "Expected an array of length {}, found {}",
+                    len,
+                    values.len()
+                ));
+            }
+            for value in values {
+                encode(idl, ty, value, data)?;
+            }
+        }
+        IdlType::Tuple(tys) => encode_tuple(idl, tys, value, data)?,
+    }
+    Ok(())
+}
+
+fn encode_ty_def(idl: &Idl, ty_def: &IdlTypeDef, value: &Value, data: &mut Vec<u8>) -> Result<()> {
+    match &ty_def.ty {
+        IdlTypeDefTy::Struct { fields, .. } => encode_fields(idl, fields, value, data),
+        IdlTypeDefTy::Tuple { fields, .. } => encode_tuple(idl, fields, value, data),
+        IdlTypeDefTy::Enum { variants, .. } => {
+            let (name, fields_value) = match value {
+                Value::String(name) => (name, None),
+                Value::Object(obj) if obj.len() == 1 => {
+                    let (name, fields_value) = obj.iter().next().unwrap();
+                    (name, Some(fields_value))
+                }
+                _ => return Err(anyhow!("Expected a variant of {}", ty_def.name)),
+            };
+            let tag = variants
+                .iter()
+                .position(|variant| &variant.name == name)
+                .ok_or_else(|| anyhow!("Invalid variant {} of {}", name, ty_def.name))?;
+            data.push(tag as u8);
+            match (&variants[tag].fields, fields_value) {
+                (None, _) => Ok(()),
+                (Some(EnumFields::Named(fields)), Some(value)) => {
+                    encode_fields(idl, fields, value, data)
+                }
+                (Some(EnumFields::Tuple(tys)), Some(value)) => encode_tuple(idl, tys, value, data),
+                (Some(_), None) => Err(anyhow!("Missing fields of variant {}", name)),
+            }
+        }
+    }
+}
+
+fn encode_tuple(idl: &Idl, tys: &[IdlType], value: &Value, data: &mut Vec<u8>) -> Result<()> {
+    let values = as_array(value)?;
+    if values.len() != tys.len() {
+        return Err(anyhow!(
+            "Expected a tuple of length {}, found {}",
+            tys.len(),
+            values.len()
+        ));
+    }
+    for (ty, value) in tys.iter().zip(values) {
+        encode(idl, ty, value, data)?;
+    }
+    Ok(())
+}
+
+fn encode_len(len: usize, data: &mut Vec<u8>) -> Result<()> {
+    let len = u32::try_from(len).map_err(|_| anyhow!("Length too large: {}", len))?;
+    data.extend_from_slice(&len.to_le_bytes());
+    Ok(())
+}
+
+fn as_array(value: &Value) -> Result<&Vec<Value>> {
+    value
+        .as_array()
+        .ok_or_else(|| anyhow!("Expected an array, found {}", value))
+}
+
+// Integers are given either as JSON numbers or as decimal strings.
+fn parse_int<T: TryFrom<u128> + TryFrom<i128>>(value: &Value) -> Result<T> {
+    let s = match value {
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => s.clone(),
+        _ => return Err(anyhow!("Expected an integer, found {}", value)),
+    };
+    let int = match (s.parse::<u128>(), s.parse::<i128>()) {
+        (Ok(n), _) => T::try_from(n).ok(),
+        (_, Ok(n)) => T::try_from(n).ok(),
+        _ => return Err(anyhow!("Invalid integer: {}", value)),
+    };
+    int.ok_or_else(|| anyhow!("Integer out of range: {}", value))
+}
+
+fn parse_float(value: &Value) -> Result<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+    .ok_or_else(|| anyhow!("Expected a number, found {}", value))
+}
+
+// User defined types, including accounts and the state struct, by name.
+fn find_ty_def<'a>(idl: &'a Idl, name: &str) -> Result<&'a IdlTypeDef> {
+    idl.types
+        .iter()
+        .chain(idl.accounts.iter())
+        .chain(idl.state.iter().map(|state| &state.strct))
+        .find(|ty_def| ty_def.name == name)
+        .ok_or_else(|| anyhow!("Type not found: {}", name))
+}
+
+// Substitutes the type arguments for the generic type definition's params.
+fn instantiate(ty_def: &IdlTypeDef, args: &[IdlType]) -> Result<IdlTypeDef> {
+    let params = match &ty_def.ty {
+        IdlTypeDefTy::Struct { generics, .. }
+        | IdlTypeDefTy::Tuple { generics, .. }
+        | IdlTypeDefTy::Enum { generics, .. } => generics,
+    };
+    if params.len() != args.len() {
+        return Err(anyhow!(
+            "{} takes {} type arguments, given {}",
+            ty_def.name,
+            params.len(),
+            args.len()
+        ));
+    }
+    let sub = |ty: &IdlType| substitute(ty, params, args);
+    let sub_fields = |fields: &[IdlField]| {
+        fields
+            .iter()
+            .map(|f| IdlField {
+                ty: sub(&f.ty),
+                ..f.clone()
+            })
+            .collect()
+    };
+    let ty = match &ty_def.ty {
+        IdlTypeDefTy::Struct { fields, .. } => IdlTypeDefTy::Struct {
+            generics: vec![],
+            fields: sub_fields(fields),
+        },
+        IdlTypeDefTy::Tuple { fields, .. } => IdlTypeDefTy::Tuple {
+            generics: vec![],
+            fields: fields.iter().map(sub).collect(),
+        },
+        IdlTypeDefTy::Enum { variants, .. } => IdlTypeDefTy::Enum {
+            generics: vec![],
+            variants: variants
+                .iter()
+                .map(|variant| {
+                    let mut variant = variant.clone();
+                    variant.fields = variant.fields.map(|fields| match fields {
+                        EnumFields::Named(fields) => EnumFields::Named(sub_fields(&fields)),
+                        EnumFields::Tuple(tys) => EnumFields::Tuple(tys.iter().map(sub).collect()),
+                    });
+                    variant
+                })
+                .collect(),
+        },
+    };
+    Ok(IdlTypeDef {
+        ty,
+        ..ty_def.clone()
+    })
+}
+
+fn substitute(ty: &IdlType, params: &[String], args: &[IdlType]) -> IdlType {
+    let sub = |ty: &IdlType| Box::new(substitute(ty, params, args));
+    match ty {
+        IdlType::Generic(name) => match params.iter().position(|p| p == name) {
+            Some(idx) => args[idx].clone(),
+            None => ty.clone(),
+        },
+        IdlType::DefinedWithGenerics { name, generics } => IdlType::DefinedWithGenerics {
+            name: name.clone(),
+            generics: generics.iter().map(|ty| *sub(ty)).collect(),
+        },
+        IdlType::Option(ty) => IdlType::Option(sub(ty)),
+        IdlType::Vec(ty) => IdlType::Vec(sub(ty)),
+        IdlType::Array(ty, len) => IdlType::Array(sub(ty), *len),
+        IdlType::Tuple(tys) => IdlType::Tuple(tys.iter().map(|ty| *sub(ty)).collect()),
+        _ => ty.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use borsh::{BorshDeserialize, BorshSerialize};
+    use serde_json::json;
+
+    // Types serialized with Borsh, as by `AnchorSerialize`, described by
+    // `idl()` below.
+    #[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq)]
+    struct Data {
+        flag: bool,
+        small: i8,
+        big: u128,
+        neg: i128,
+        ratio: f64,
+        name: String,
+        bytes: Vec<u8>,
+        key: [u8; 32],
+        opt: Option<u16>,
+        none: Option<u16>,
+        list: Vec<Inner>,
+        arr: [u32; 3],
+        pair: (u8, String),
+        kinds: Vec<Kind>,
+        wrapper: Wrapper,
+    }
+
+    #[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+    struct Inner {
+        value: u64,
+    }
+
+    #[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq)]
+    enum Kind {
+        Unit,
+        Named { a: u8 },
+        Tuple(u16, i32),
+    }
+
+    #[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq)]
+    struct Wrapper(i32);
+
+    #[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq)]
+    struct Pod {
+        amount: u64,
+        authority: [u8; 32],
+        history: [Inner; 2],
+    }
+
+    fn idl() -> Idl {
+        serde_json::from_value(json!({
+            "version": "0.1.0",
+            "name": "coder",
+            "instructions": [{
+                "name": "setData",
+                "accounts": [],
+                "args": [
+                    { "name": "data", "type": { "defined": "Data" } },
+                    { "name": "amount", "type": "u64" },
+                ],
+            }],
+            "accounts": [
+                {
+                    "name": "Data",
+                    "type": {
+                        "kind": "struct",
+                        "fields": [
+                            { "name": "flag", "type": "bool" },
+                            { "name": "small", "type": "i8" },
+                            { "name": "big", "type": "u128" },
+                            { "name": "neg", "type": "i128" },
+                            { "name": "ratio", "type": "f64" },
+                            { "name": "name", "type": "string" },
+                            { "name": "bytes", "type": "bytes" },
+                            { "name": "key", "type": "publicKey" },
+                            { "name": "opt", "type": { "option": "u16" } },
+                            { "name": "none", "type": { "option": "u16" } },
+                            { "name": "list", "type": { "vec": { "defined": "Inner" } } },
+                            { "name": "arr", "type": { "array": ["u32", 3] } },
+                            { "name": "pair", "type": { "tuple": ["u8", "string"] } },
+                            { "name": "kinds", "type": { "vec": { "defined": "Kind" } } },
+                            { "name": "wrapper", "type": { "defined": "Wrapper" } },
+                        ],
+                    },
+                },
+                {
+                    "name": "Pod",
+                    "zeroCopy": true,
+                    "type": {
+                        "kind": "struct",
+                        "fields": [
+                            { "name": "amount", "type": "u64" },
+                            { "name": "authority", "type": "publicKey" },
+                            { "name": "history", "type": { "array": [{ "defined": "Inner" }, 2] } },
+                        ],
+                    },
+                },
+                {
+                    "name": "NotPod",
+                    "zeroCopy": true,
+                    "type": {
+                        "kind": "struct",
+                        "fields": [
+                            { "name": "amount", "type": "u64" },
+                            { "name": "items", "type": { "vec": "u64" } },
+                        ],
+                    },
+                },
+                {
+                    "name": "Cyclic",
+                    "zeroCopy": true,
+                    "type": {
+                        "kind": "struct",
+                        "fields": [
+                            { "name": "next", "type": { "array": [{ "defined": "Cyclic" }, 1] } },
+                        ],
+                    },
+                },
+            ],
+            "types": [
+                {
+                    "name": "Inner",
+                    "type": {
+                        "kind": "struct",
+                        "fields": [{ "name": "value", "type": "u64" }],
+                    },
+                },
+                {
+                    "name": "Kind",
+                    "type": {
+                        "kind": "enum",
+                        "variants": [
+                            { "name": "Unit" },
+                            { "name": "Named", "fields": [{ "name": "a", "type": "u8" }] },
+                            { "name": "Tuple", "fields": ["u16", "i32"] },
+                        ],
+                    },
+                },
+                {
+                    "name": "Wrapper",
+                    "type": { "kind": "tuple", "fields": ["i32"] },
+                },
+            ],
+        }))
+        .unwrap()
+    }
+
+    fn data() -> (Data, Value) {
+        let key = [7u8; 32];
+        let data = Data {
+            flag: true,
+            small: -5,
+            big: u128::MAX,
+            neg: i128::MIN,
+            ratio: 1.5,
+            name: "anchor".to_string(),
+            bytes: vec![1, 2, 3],
+            key,
+            opt: Some(42),
+            none: None,
+            list: vec![Inner { value: 1 }, Inner { value: u64::MAX }],
+            arr: [1, 2, 3],
+            pair: (9, "pair".to_string()),
+            kinds: vec![Kind::Unit, Kind::Named { a: 8 }, Kind::Tuple(16, -32)],
+            wrapper: Wrapper(-1),
+        };
+        let value = json!({
+            "flag": true,
+            "small": -5,
+            "big": u128::MAX.to_string(),
+            "neg": i128::MIN.to_string(),
+            "ratio": 1.5,
+            "name": "anchor",
+            "bytes": [1, 2, 3],
+            "key": bs58::encode(key).into_string(),
+            "opt": 42,
+            "none": null,
+            "list": [{ "value": 1 }, { "value": u64::MAX }],
+            "arr": [1, 2, 3],
+            "pair": [9, "pair"],
+            "kinds": ["Unit", { "Named": { "a": 8 } }, { "Tuple": [16, -32] }],
+            "wrapper": [-1],
+        });
+        (data, value)
+    }
+
+    fn account_data<T: BorshSerialize>(name: &str, account: &T) -> Vec<u8> {
+        let mut data = account_discriminator(name).to_vec();
+        data.extend(account.try_to_vec().unwrap());
+        data
+    }
+
+    #[test]
+    fn encode_matches_borsh() {
+        let (data, value) = data();
+        let mut encoded = Vec::new();
+        encode(
+            &idl(),
+            &IdlType::Defined("Data".to_string()),
+            &value,
+            &mut encoded,
+        )
+        .unwrap();
+        assert_eq!(encoded, data.try_to_vec().unwrap());
+        assert_eq!(Data::try_from_slice(&encoded).unwrap(), data);
+    }
+
+    #[test]
+    fn decode_matches_borsh() {
+        let (data, value) = data();
+        let bytes = data.try_to_vec().unwrap();
+        let decoded = decode(
+            &idl(),
+            &IdlType::Defined("Data".to_string()),
+            &mut &bytes[..],
+        );
+        assert_eq!(decoded.unwrap(), value);
+    }
+
+    #[test]
+    fn encode_accepts_decimal_strings() {
+        let mut encoded = Vec::new();
+        encode(
+            &idl(),
+            &IdlType::U64,
+            &json!("18446744073709551615"),
+            &mut encoded,
+        )
+        .unwrap();
+        assert_eq!(encoded, u64::MAX.try_to_vec().unwrap());
+        assert!(encode(&idl(), &IdlType::U8, &json!(256), &mut Vec::new()).is_err());
+    }
+
+    #[test]
+    fn decode_account_checks_discriminator() {
+        let (data, value) = data();
+        let mut bytes = account_data("Data", &data);
+        // Trailing bytes of the account's allocated space are ignored.
+        bytes.extend_from_slice(&[0; 16]);
+        assert_eq!(decode_account(&idl(), "Data", &bytes).unwrap(), value);
+        assert!(decode_account(&idl(), "Pod", &bytes).is_err());
+        assert!(decode_account(&idl(), "Data", &bytes[..4]).is_err());
+    }
+
+    #[test]
+    fn decode_zero_copy_account() {
+        let pod = Pod {
+            amount: 10,
+            authority: [3; 32],
+            history: [Inner { value: 1 }, Inner { value: 2 }],
+        };
+        let bytes = account_data("Pod", &pod);
+        assert_eq!(
+            decode_account(&idl(), "Pod", &bytes).unwrap(),
+            json!({
+                "amount": 10,
+                "authority": bs58::encode([3u8; 32]).into_string(),
+                "history": [{ "value": 1 }, { "value": 2 }],
+            })
+        );
+
+        let bytes = account_discriminator("NotPod").to_vec();
+        let err = decode_account(&idl(), "NotPod", &bytes).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Unable to decode field items of zero copy type NotPod"
+        );
+
+        let bytes = account_discriminator("Cyclic").to_vec();
+        let err = decode_account(&idl(), "Cyclic", &bytes).unwrap_err();
+        assert_eq!(err.to_string(), "Zero copy type Cyclic contains itself");
+    }
+
+    #[test]
+    fn instruction_round_trip() {
+        let (data, value) = data();
+        let args = json!({ "data": value, "amount": 5 });
+        let idl = idl();
+        let (ix, encoded) = encode_instruction(&idl, "set_data", &args).unwrap();
+        assert_eq!(ix.name, "setData");
+
+        let mut expected = sighash(SIGHASH_GLOBAL_NAMESPACE, "set_data").to_vec();
+        expected.extend(data.try_to_vec().unwrap());
+        expected.extend(5u64.try_to_vec().unwrap());
+        assert_eq!(encoded, expected);
+
+        let decoded = decode_instruction(&idl, &encoded).unwrap();
+        assert_eq!(decoded.name, "setData");
+        assert_eq!(decoded.args, args);
+    }
+}
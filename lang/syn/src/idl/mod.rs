@@ -0,0 +1,352 @@
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+
+pub mod coder;
+
+/// Prefix of every line of test output holding an IDL fragment printed by the
+/// code generated for the `idl-build` feature, followed by the fragment's kind
+/// and its JSON.
+pub const IDL_FRAGMENT_PREFIX: &str = "__anchor_idl_fragment";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Idl {
+    pub version: String,
+    pub name: String,
+    pub instructions: Vec<IdlIx>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub state: Option<IdlState>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub accounts: Vec<IdlTypeDef>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub types: Vec<IdlTypeDef>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub events: Option<Vec<IdlEvent>>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub errors: Option<Vec<IdlErrorCode>>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub metadata: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdlState {
+    #[serde(rename = "struct")]
+    pub strct: IdlTypeDef,
+    pub methods: Vec<IdlStateMethod>,
+}
+
+pub type IdlStateMethod = IdlIx;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdlIx {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub docs: Option<Vec<String>>,
+    pub accounts: Vec<IdlAccountItem>,
+    pub args: Vec<IdlField>,
+}
+
+// A single struct deriving `Accounts`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IdlAccounts {
+    pub name: String,
+    pub accounts: Vec<IdlAccountItem>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum IdlAccountItem {
+    IdlAccount(IdlAccount),
+    IdlAccounts(IdlAccounts),
+}
+
+// A single field in the accounts struct.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IdlAccount {
+    pub name: String,
+    pub is_mut: bool,
+    pub is_signer: bool,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub docs: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub pda: Option<IdlPda>,
+    // Accounts that must be stored in this account's data, given by
+    // `belongs_to`/`has_one` constraints.
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub relations: Vec<String>,
+    // Owner of the account, given by an `owner` constraint.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub owner: Option<IdlAccountOwner>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum IdlAccountOwner {
+    // The program executing the instruction, i.e., `owner = program`.
+    Program,
+    // Any program, i.e., `owner = skip`.
+    Skip,
+}
+
+// Seeds of a program derived address, given by a `seeds` constraint.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdlPda {
+    pub seeds: Vec<IdlSeed>,
+    // True if the program finds the canonical bump seed. Otherwise, the bump
+    // is given as the last seed.
+    pub bump: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "kind")]
+pub enum IdlSeed {
+    // Constant bytes, e.g., `b"vault"`.
+    Const { value: Vec<u8> },
+    // Address of another account in the accounts struct.
+    Account { path: String },
+    // Any other seed, e.g., account data, as written in the source.
+    Expr { expr: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdlField {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub ty: IdlType,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub docs: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdlEvent {
+    pub name: String,
+    pub fields: Vec<IdlField>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdlTypeDef {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub docs: Option<Vec<String>>,
+    #[serde(rename = "type")]
+    pub ty: IdlTypeDefTy,
+    // True for `#[account(zero_copy)]` accounts, whose data is the struct's
+    // `#[repr(C)]` layout rather than its Borsh serialization.
+    #[serde(rename = "zeroCopy", skip_serializing_if = "is_false", default)]
+    pub zero_copy: bool,
+}
+
+fn is_false(b: &bool) -> bool {
+    !b
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase", tag = "kind")]
+pub enum IdlTypeDefTy {
+    Struct {
+        #[serde(skip_serializing_if = "Vec::is_empty", default)]
+        generics: Vec<String>,
+        fields: Vec<IdlField>,
+    },
+    // Tuple struct, e.g., a newtype wrapper.
+    Tuple {
+        #[serde(skip_serializing_if = "Vec::is_empty", default)]
+        generics: Vec<String>,
+        fields: Vec<IdlType>,
+    },
+    Enum {
+        #[serde(skip_serializing_if = "Vec::is_empty", default)]
+        generics: Vec<String>,
+        variants: Vec<EnumVariant>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnumVariant {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub fields: Option<EnumFields>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum EnumFields {
+    Named(Vec<IdlField>),
+    Tuple(Vec<IdlType>),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum IdlType {
+    Bool,
+    U8,
+    I8,
+    U16,
+    I16,
+    U32,
+    I32,
+    U64,
+    I64,
+    U128,
+    I128,
+    F32,
+    F64,
+    Bytes,
+    String,
+    PublicKey,
+    Defined(String),
+    // User defined type instantiated with the given type arguments.
+    DefinedWithGenerics {
+        name: String,
+        generics: Vec<IdlType>,
+    },
+    // Type parameter of the enclosing generic type definition.
+    Generic(String),
+    Option(Box<IdlType>),
+    Vec(Box<IdlType>),
+    Array(Box<IdlType>, usize),
+    Tuple(Vec<IdlType>),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdlTypePublicKey;
+
+impl std::str::FromStr for IdlType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let ty: syn::Type = syn::parse_str(s)?;
+        IdlType::try_from(&ty)
+    }
+}
+
+impl TryFrom<&syn::Type> for IdlType {
+    type Error = anyhow::Error;
+
+    fn try_from(ty: &syn::Type) -> Result<Self, Self::Error> {
+        IdlType::from_generic_type(ty, &[])
+    }
+}
+
+impl IdlType {
+    /// Converts a type appearing in a definition with the given generic type
+    /// parameters, each of which becomes an `IdlType::Generic`.
+    pub fn from_generic_type(ty: &syn::Type, generics: &[String]) -> anyhow::Result<Self> {
+        let r = match ty {
+            syn::Type::Path(ty_path) => {
+                if ty_path.qself.is_some() {
+                    return Err(anyhow::anyhow!("Qualified self types are not supported"));
+                }
+                // Module qualified types resolve to their name, e.g.,
+                // `state::Kind` to `Kind`, since types from all modules share
+                // the IDL's namespace.
+                let segment = ty_path
+                    .path
+                    .segments
+                    .last()
+                    .ok_or_else(|| anyhow::anyhow!("Invalid type path"))?;
+                let name = segment.ident.to_string();
+                match &segment.arguments {
+                    syn::PathArguments::None => match name.as_str() {
+                        "bool" => IdlType::Bool,
+                        "u8" => IdlType::U8,
+                        "i8" => IdlType::I8,
+                        "u16" => IdlType::U16,
+                        "i16" => IdlType::I16,
+                        "u32" => IdlType::U32,
+                        "i32" => IdlType::I32,
+                        "u64" => IdlType::U64,
+                        "i64" => IdlType::I64,
+                        "u128" => IdlType::U128,
+                        "i128" => IdlType::I128,
+                        "f32" => IdlType::F32,
+                        "f64" => IdlType::F64,
+                        "String" => IdlType::String,
+                        "Pubkey" => IdlType::PublicKey,
+                        _ if generics.contains(&name) => IdlType::Generic(name),
+                        _ => IdlType::Defined(name),
+                    },
+                    syn::PathArguments::AngleBracketed(args) => {
+                        let tys: Vec<&syn::Type> = args
+                            .args
+                            .iter()
+                            .filter_map(|arg| match arg {
+                                syn::GenericArgument::Type(ty) => Some(ty),
+                                _ => None,
+                            })
+                            .collect();
+                        match (name.as_str(), tys.as_slice()) {
+                            ("Option", [ty]) => {
+                                IdlType::Option(Box::new(IdlType::from_generic_type(ty, generics)?))
+                            }
+                            ("Vec", [ty]) => match IdlType::from_generic_type(ty, generics)? {
+                                IdlType::U8 => IdlType::Bytes,
+                                inner_ty => IdlType::Vec(Box::new(inner_ty)),
+                            },
+                            // Borsh serializes a box as its contents.
+                            ("Box", [ty]) => IdlType::from_generic_type(ty, generics)?,
+                            // Lifetime arguments aren't serialized.
+                            (_, []) => IdlType::Defined(name),
+                            (_, tys) => IdlType::DefinedWithGenerics {
+                                name,
+                                generics: tys
+                                    .iter()
+                                    .map(|ty| IdlType::from_generic_type(ty, generics))
+                                    .collect::<anyhow::Result<Vec<_>>>()?,
+                            },
+                        }
+                    }
+                    syn::PathArguments::Parenthesized(_) => {
+                        return Err(anyhow::anyhow!("Function types are not supported"))
+                    }
+                }
+            }
+            syn::Type::Array(ty_array) => {
+                let len = match &ty_array.len {
+                    syn::Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Int(len),
+                        ..
+                    }) => len.base10_parse::<usize>()?,
+                    // The length can't be evaluated, e.g., a constant outside
+                    // the crate, so the array is named by its definition.
+                    _ => {
+                        let mut name = quote::ToTokens::to_token_stream(ty).to_string();
+                        name.retain(|c| !c.is_whitespace());
+                        return Ok(IdlType::Defined(name));
+                    }
+                };
+                IdlType::Array(
+                    Box::new(IdlType::from_generic_type(&ty_array.elem, generics)?),
+                    len,
+                )
+            }
+            syn::Type::Tuple(ty_tuple) => IdlType::Tuple(
+                ty_tuple
+                    .elems
+                    .iter()
+                    .map(|ty| IdlType::from_generic_type(ty, generics))
+                    .collect::<Result<Vec<_>, _>>()?,
+            ),
+            syn::Type::Paren(ty_paren) => IdlType::from_generic_type(&ty_paren.elem, generics)?,
+            syn::Type::Group(ty_group) => IdlType::from_generic_type(&ty_group.elem, generics)?,
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "Unsupported type: {}",
+                    quote::ToTokens::to_token_stream(ty)
+                ))
+            }
+        };
+        Ok(r)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdlErrorCode {
+    pub code: u32,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub msg: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub docs: Option<Vec<String>>,
+}
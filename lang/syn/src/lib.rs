@@ -1,7 +1,7 @@
 //! DSL syntax tokens.
 
 #[cfg(feature = "idl")]
-use crate::idl::{IdlAccount, IdlAccountItem, IdlAccounts};
+use crate::idl::{IdlAccount, IdlAccountItem, IdlAccountOwner, IdlAccounts, IdlPda, IdlSeed};
 use anyhow::Result;
 #[cfg(feature = "idl")]
 use heck::MixedCase;
@@ -98,11 +98,11 @@ impl AccountsStruct {
         let mut tys = vec![];
         for f in &self.fields {
             match f {
-                AccountField::Field(f) => {
-                    if let Ty::ProgramAccount(pty) = &f.ty {
-                        tys.push(pty.account_ident.to_string());
-                    }
-                }
+                AccountField::Field(f) => match &f.ty {
+                    Ty::ProgramAccount(pty) => tys.push(pty.account_name()),
+                    Ty::ZeroCopyAccount(zty) => tys.push(zty.account_name()),
+                    _ => {}
+                },
                 AccountField::AccountsStruct(comp_f) => {
                     let accs = global_accs.get(&comp_f.symbol).ok_or_else(|| {
                         anyhow::format_err!("Invalid account type: {}", comp_f.symbol)
@@ -132,11 +132,7 @@ impl AccountsStruct {
                         accounts,
                     })
                 }
-                AccountField::Field(acc) => IdlAccountItem::IdlAccount(IdlAccount {
-                    name: acc.ident.to_string().to_mixed_case(),
-                    is_mut: acc.is_mut,
-                    is_signer: acc.is_signer,
-                }),
+                AccountField::Field(acc) => IdlAccountItem::IdlAccount(acc.idl_account()),
             })
             .collect::<Vec<_>>()
     }
@@ -170,32 +166,93 @@ pub struct Field {
     pub is_mut: bool,
     pub is_signer: bool,
     pub is_init: bool,
+    pub docs: Option<Vec<String>>,
 }
 
 impl Field {
+    #[cfg(feature = "idl")]
+    pub fn idl_account(&self) -> IdlAccount {
+        IdlAccount {
+            name: self.ident.to_string().to_mixed_case(),
+            is_mut: self.is_mut,
+            is_signer: self.is_signer,
+            docs: self.docs.clone(),
+            pda: self.seeds_constraint().map(ConstraintSeeds::idl_pda),
+            relations: self
+                .constraints
+                .iter()
+                .filter_map(|c| match c {
+                    Constraint::BelongsTo(c) => Some(c.join_target.to_string().to_mixed_case()),
+                    _ => None,
+                })
+                .collect(),
+            owner: self.constraints.iter().find_map(|c| match c {
+                Constraint::Owner(ConstraintOwner::Program) => Some(IdlAccountOwner::Program),
+                Constraint::Owner(ConstraintOwner::Skip) => Some(IdlAccountOwner::Skip),
+                _ => None,
+            }),
+        }
+    }
+
+    // Returns the init constraint, if the program is responsible for creating
+    // the account.
+    pub fn init_constraint(&self) -> Option<&ConstraintInit> {
+        self.constraints.iter().find_map(|c| match c {
+            Constraint::Init(c) => Some(c),
+            _ => None,
+        })
+    }
+
+    pub fn close_constraint(&self) -> Option<&ConstraintClose> {
+        self.constraints.iter().find_map(|c| match c {
+            Constraint::Close(c) => Some(c),
+            _ => None,
+        })
+    }
+
+    pub fn seeds_constraint(&self) -> Option<&ConstraintSeeds> {
+        self.constraints.iter().find_map(|c| match c {
+            Constraint::Seeds(c) => Some(c),
+            _ => None,
+        })
+    }
+
     pub fn typed_ident(&self) -> proc_macro2::TokenStream {
         let name = &self.ident;
 
         let ty = match &self.ty {
             Ty::AccountInfo => quote! { AccountInfo },
             Ty::ProgramState(ty) => {
-                let account = &ty.account_ident;
+                let account = &ty.account_type_path;
                 quote! {
                     ProgramState<#account>
                 }
             }
             Ty::ProgramAccount(ty) => {
-                let account = &ty.account_ident;
+                let account = &ty.account_type_path;
                 quote! {
                     ProgramAccount<#account>
                 }
             }
             Ty::CpiAccount(ty) => {
-                let account = &ty.account_ident;
+                let account = &ty.account_type_path;
                 quote! {
                     CpiAccount<#account>
                 }
             }
+            Ty::ZeroCopyAccount(ty) => {
+                let account = &ty.account_type_path;
+                quote! {
+                    ZeroCopyAccount<#account>
+                }
+            }
+            Ty::Program(ty) => {
+                let program = &ty.program_type_path;
+                quote! {
+                    Program<#program>
+                }
+            }
+            Ty::Signer => quote! { Signer },
             Ty::Sysvar(ty) => {
                 let account = match ty {
                     SysvarTy::Clock => quote! {Clock},
@@ -228,6 +285,9 @@ pub enum Ty {
     ProgramState(ProgramStateTy),
     ProgramAccount(ProgramAccountTy),
     CpiAccount(CpiAccountTy),
+    ZeroCopyAccount(ZeroCopyAccountTy),
+    Program(ProgramTy),
+    Signer,
     Sysvar(SysvarTy),
 }
 
@@ -247,19 +307,50 @@ pub enum SysvarTy {
 
 #[derive(Debug, PartialEq)]
 pub struct ProgramStateTy {
-    pub account_ident: syn::Ident,
+    pub account_type_path: syn::TypePath,
 }
 
 #[derive(Debug, PartialEq)]
 pub struct ProgramAccountTy {
-    // The struct type of the account.
-    pub account_ident: syn::Ident,
+    // The struct type of the account, possibly module qualified.
+    pub account_type_path: syn::TypePath,
+}
+
+impl ProgramAccountTy {
+    // Name of the account struct, without any module qualification.
+    pub fn account_name(&self) -> String {
+        type_path_name(&self.account_type_path)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ZeroCopyAccountTy {
+    // The struct type of the account, possibly module qualified.
+    pub account_type_path: syn::TypePath,
+}
+
+impl ZeroCopyAccountTy {
+    // Name of the account struct, without any module qualification.
+    pub fn account_name(&self) -> String {
+        type_path_name(&self.account_type_path)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ProgramTy {
+    // The type implementing `Id` for the program, possibly module qualified.
+    pub program_type_path: syn::TypePath,
 }
 
 #[derive(Debug, PartialEq)]
 pub struct CpiAccountTy {
-    // The struct type of the account.
-    pub account_ident: syn::Ident,
+    // The struct type of the account, possibly module qualified.
+    pub account_type_path: syn::TypePath,
+}
+
+// Returns the last segment of the given path, i.e., the name of the type.
+fn type_path_name(type_path: &syn::TypePath) -> String {
+    type_path.path.segments.last().unwrap().ident.to_string()
 }
 
 // An access control constraint for an account.
@@ -271,6 +362,8 @@ pub enum Constraint {
     Owner(ConstraintOwner),
     RentExempt(ConstraintRentExempt),
     Seeds(ConstraintSeeds),
+    Init(ConstraintInit),
+    Close(ConstraintClose),
 }
 
 #[derive(Debug)]
@@ -301,6 +394,107 @@ pub enum ConstraintRentExempt {
 #[derive(Debug)]
 pub struct ConstraintSeeds {
     pub seeds: proc_macro2::Group,
+    // True if the canonical bump seed should be found by the program, rather
+    // than given as the last seed.
+    pub bump: bool,
+}
+
+#[cfg(feature = "idl")]
+impl ConstraintSeeds {
+    // Seeds that don't parse as expressions are kept as a single seed, as
+    // written in the source.
+    pub fn idl_pda(&self) -> IdlPda {
+        use syn::parse::Parser;
+        let seeds = match syn::punctuated::Punctuated::<syn::Expr, syn::Token![,]>::parse_terminated
+            .parse2(self.seeds.stream())
+        {
+            Ok(seeds) => seeds.iter().map(idl_seed).collect(),
+            Err(_) => vec![IdlSeed::Expr {
+                expr: self.seeds.stream().to_string(),
+            }],
+        };
+        IdlPda {
+            seeds,
+            bump: self.bump,
+        }
+    }
+}
+
+// Resolves a seed expression into constant bytes or the address of an
+// account, if possible, after stripping any references and conversions into
+// bytes, e.g., `authority.key.as_ref()` resolves to the `authority` account.
+#[cfg(feature = "idl")]
+fn idl_seed(seed: &syn::Expr) -> IdlSeed {
+    let mut expr = seed;
+    let mut is_key = false;
+    loop {
+        match expr {
+            syn::Expr::Reference(r) => expr = &r.expr,
+            syn::Expr::Paren(p) => expr = &p.expr,
+            syn::Expr::MethodCall(m) if m.args.is_empty() => {
+                match m.method.to_string().as_str() {
+                    "as_ref" | "as_bytes" | "to_bytes" | "to_account_info" => {}
+                    "key" => is_key = true,
+                    _ => break,
+                }
+                expr = &m.receiver;
+            }
+            syn::Expr::Field(f) => match &f.member {
+                syn::Member::Named(member) if member == "key" => {
+                    is_key = true;
+                    expr = &f.base;
+                }
+                _ => break,
+            },
+            _ => break,
+        }
+    }
+    let resolved = match expr {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::ByteStr(s),
+            ..
+        }) => Some(IdlSeed::Const { value: s.value() }),
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Str(s),
+            ..
+        }) => Some(IdlSeed::Const {
+            value: s.value().into_bytes(),
+        }),
+        syn::Expr::Array(a) => a
+            .elems
+            .iter()
+            .map(|elem| match elem {
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Int(int),
+                    ..
+                }) => int.base10_parse::<u8>().ok(),
+                _ => None,
+            })
+            .collect::<Option<Vec<u8>>>()
+            .map(|value| IdlSeed::Const { value }),
+        syn::Expr::Path(p) if is_key => p.path.get_ident().map(|ident| IdlSeed::Account {
+            path: ident.to_string().to_mixed_case(),
+        }),
+        _ => None,
+    };
+    resolved.unwrap_or_else(|| IdlSeed::Expr {
+        expr: parser::tts_to_string(seed),
+    })
+}
+
+// Creates the account via the system program, before deserialization.
+#[derive(Debug)]
+pub struct ConstraintInit {
+    // Field in the accounts struct funding the new account.
+    pub payer: proc_macro2::Ident,
+    // Expression evaluating to the number of bytes to allocate.
+    pub space: proc_macro2::TokenStream,
+}
+
+// Closes the account upon exit, sending its lamports to the `sol_dest` field.
+#[derive(Debug)]
+pub struct ConstraintClose {
+    pub sol_dest: proc_macro2::Ident,
 }
 
 #[derive(Debug)]
@@ -316,4 +510,48 @@ pub struct ErrorCode {
     pub id: u32,
     pub ident: syn::Ident,
     pub msg: Option<String>,
+    pub docs: Option<Vec<String>>,
 }
+
+// Error codes reserved by the framework, given as `(code, name, msg)`, from
+// which `anchor_lang::ErrorCode` is generated and which every IDL exports.
+// Codes 20 and up are returned by the `Accounts` derive for violated
+// constraints.
+pub const FRAMEWORK_ERROR_CODES: &[(u32, &str, &str)] = &[
+    (
+        1,
+        "WrongSerialization",
+        "Failed to serialize or deserialize data",
+    ),
+    (
+        2,
+        "AccountDidNotSerialize",
+        "Failed to serialize the account, its data may exceed the allocated space",
+    ),
+    (
+        3,
+        "DeclaredProgramIdMismatch",
+        "The declared program id does not match the actual program id",
+    ),
+    (4, "InvalidProgramId", "The program id was not as expected"),
+    (
+        5,
+        "InvalidProgramExecutable",
+        "The program account is not executable",
+    ),
+    (
+        20,
+        "ConstraintBelongsTo",
+        "A belongs_to constraint was violated",
+    ),
+    (21, "ConstraintLiteral", "A literal constraint was violated"),
+    (22, "ConstraintOwner", "An owner constraint was violated"),
+    (
+        23,
+        "ConstraintRentExempt",
+        "A rent exemption constraint was violated",
+    ),
+    (24, "ConstraintSeeds", "A seeds constraint was violated"),
+    (25, "ConstraintClose", "A close constraint was violated"),
+    (26, "ConstraintSigner", "A signer constraint was violated"),
+];
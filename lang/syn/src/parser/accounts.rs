@@ -1,23 +1,65 @@
+use crate::parser;
 use crate::{
-    AccountField, AccountsStruct, CompositeField, Constraint, ConstraintBelongsTo,
-    ConstraintLiteral, ConstraintOwner, ConstraintRentExempt, ConstraintSeeds, ConstraintSigner,
-    CpiAccountTy, Field, ProgramAccountTy, ProgramStateTy, SysvarTy, Ty,
+    AccountField, AccountsStruct, CompositeField, Constraint, ConstraintBelongsTo, ConstraintClose,
+    ConstraintInit, ConstraintLiteral, ConstraintOwner, ConstraintRentExempt, ConstraintSeeds,
+    ConstraintSigner, CpiAccountTy, Field, ProgramAccountTy, ProgramStateTy, ProgramTy, SysvarTy,
+    Ty, ZeroCopyAccountTy,
 };
+use proc_macro2::TokenTree;
+use std::iter::Peekable;
 
-pub fn parse(strct: &syn::ItemStruct) -> AccountsStruct {
+// Keywords accepted inside an `#[account(..)]` attribute, in addition to
+// string literal constraints.
+const CONSTRAINT_KEYWORDS: &[&str] = &[
+    "init",
+    "mut",
+    "signer",
+    "seeds",
+    "bump",
+    "close",
+    "belongs_to",
+    "has_one",
+    "owner",
+    "rent_exempt",
+    "payer",
+    "space",
+];
+
+// Parses the struct, accumulating the errors from *all* malformed fields so
+// that they're reported in a single pass.
+pub fn parse(strct: &syn::ItemStruct) -> syn::Result<AccountsStruct> {
     let fields = match &strct.fields {
-        syn::Fields::Named(fields) => fields.named.iter().map(parse_account_field).collect(),
-        _ => panic!("invalid input"),
+        syn::Fields::Named(fields) => fields.named.iter().map(parse_account_field),
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &strct.fields,
+                "Accounts structs must have named fields",
+            ))
+        }
     };
-    AccountsStruct::new(strct.clone(), fields)
+    let mut account_fields = vec![];
+    let mut error: Option<syn::Error> = None;
+    for f in fields {
+        match f {
+            Ok(f) => account_fields.push(f),
+            Err(e) => match error.as_mut() {
+                None => error = Some(e),
+                Some(error) => error.combine(e),
+            },
+        }
+    }
+    match error {
+        Some(error) => Err(error),
+        None => Ok(AccountsStruct::new(strct.clone(), account_fields)),
+    }
 }
 
-fn parse_account_field(f: &syn::Field) -> AccountField {
-    let anchor_attr = parse_account_attr(f);
+fn parse_account_field(f: &syn::Field) -> syn::Result<AccountField> {
+    let anchor_attr = parse_account_attr(f)?;
     parse_field(f, anchor_attr)
 }
 
-fn parse_account_attr(f: &syn::Field) -> Option<&syn::Attribute> {
+fn parse_account_attr(f: &syn::Field) -> syn::Result<Option<&syn::Attribute>> {
     let anchor_attrs: Vec<&syn::Attribute> = f
         .attrs
         .iter()
@@ -32,21 +74,30 @@ fn parse_account_attr(f: &syn::Field) -> Option<&syn::Attribute> {
         })
         .collect();
     match anchor_attrs.len() {
-        0 => None,
-        1 => Some(anchor_attrs[0]),
-        _ => panic!("Invalid syntax: please specify one account attribute."),
+        0 => Ok(None),
+        1 => Ok(Some(anchor_attrs[0])),
+        _ => Err(syn::Error::new_spanned(
+            anchor_attrs[1],
+            "please specify one account attribute per field",
+        )),
     }
 }
 
-fn parse_field(f: &syn::Field, anchor: Option<&syn::Attribute>) -> AccountField {
+fn parse_field(f: &syn::Field, anchor: Option<&syn::Attribute>) -> syn::Result<AccountField> {
     let ident = f.ident.clone().unwrap();
-    let (constraints, is_mut, is_signer, is_init) = match anchor {
+    let (mut constraints, is_mut, mut is_signer, is_init) = match anchor {
         None => (vec![], false, false, false),
-        Some(anchor) => parse_constraints(anchor),
+        Some(anchor) => parse_constraints(anchor)?,
     };
-    match is_field_primitive(f) {
+    let field = match is_field_primitive(f)? {
         true => {
-            let ty = parse_ty(f);
+            let ty = parse_ty(f)?;
+            // The `Signer` type always requires a signature, checked like
+            // the `signer` constraint.
+            if ty == Ty::Signer && !is_signer {
+                is_signer = true;
+                constraints.push(Constraint::Signer(ConstraintSigner {}));
+            }
             AccountField::Field(Field {
                 ident,
                 ty,
@@ -54,104 +105,171 @@ fn parse_field(f: &syn::Field, anchor: Option<&syn::Attribute>) -> AccountField
                 is_mut,
                 is_signer,
                 is_init,
+                docs: parser::docs(&f.attrs),
             })
         }
         false => AccountField::AccountsStruct(CompositeField {
             ident,
-            symbol: ident_string(f),
+            symbol: ident_string(f)?,
             constraints,
             raw_field: f.clone(),
         }),
+    };
+    if let Some(anchor) = anchor {
+        validate_constraints(&field, anchor)?;
     }
+    Ok(field)
 }
 
-fn is_field_primitive(f: &syn::Field) -> bool {
-    match ident_string(f).as_str() {
-        "ProgramState" | "ProgramAccount" | "CpiAccount" | "Sysvar" | "AccountInfo" => true,
-        _ => false,
+// Rejects constraints that can't be checked for the field's type, e.g., an
+// `owner` constraint on a `CpiAccount`, which is checked by the type itself.
+fn validate_constraints(field: &AccountField, anchor: &syn::Attribute) -> syn::Result<()> {
+    let f = match field {
+        AccountField::Field(f) => f,
+        AccountField::AccountsStruct(s) => {
+            if s.constraints
+                .iter()
+                .any(|c| !matches!(c, Constraint::Literal(_)))
+            {
+                return Err(syn::Error::new_spanned(
+                    anchor,
+                    "composite fields can only use string literal constraints",
+                ));
+            }
+            return Ok(());
+        }
+    };
+    let has_info = matches!(
+        f.ty,
+        Ty::AccountInfo | Ty::ProgramAccount(_) | Ty::ZeroCopyAccount(_)
+    );
+    for c in &f.constraints {
+        let constraint = match c {
+            Constraint::Signer(_) if !has_info && f.ty != Ty::Signer => "signer",
+            Constraint::Owner(_) if !has_info => "owner",
+            // Rent exemption is enforced by default upon `init`.
+            Constraint::RentExempt(_) if !has_info && f.is_init => "init",
+            Constraint::RentExempt(_) if !has_info => "rent_exempt",
+            _ => continue,
+        };
+        return Err(syn::Error::new_spanned(
+            anchor,
+            format!(
+                "the `{}` constraint can only be used with `AccountInfo`, `ProgramAccount` \
+                 or `ZeroCopyAccount` fields",
+                constraint
+            ),
+        ));
     }
+    Ok(())
+}
+
+fn is_field_primitive(f: &syn::Field) -> syn::Result<bool> {
+    Ok(matches!(
+        ident_string(f)?.as_str(),
+        "ProgramState"
+            | "ProgramAccount"
+            | "CpiAccount"
+            | "ZeroCopyAccount"
+            | "Program"
+            | "Signer"
+            | "Sysvar"
+            | "AccountInfo"
+    ))
 }
 
-fn parse_ty(f: &syn::Field) -> Ty {
+fn parse_ty(f: &syn::Field) -> syn::Result<Ty> {
     let path = match &f.ty {
         syn::Type::Path(ty_path) => ty_path.path.clone(),
-        _ => panic!("invalid account syntax"),
+        _ => return Err(invalid_account_type(&f.ty)),
     };
-    match ident_string(f).as_str() {
-        "ProgramState" => Ty::ProgramState(parse_program_state(&path)),
-        "ProgramAccount" => Ty::ProgramAccount(parse_program_account(&path)),
-        "CpiAccount" => Ty::CpiAccount(parse_cpi_account(&path)),
-        "Sysvar" => Ty::Sysvar(parse_sysvar(&path)),
+    let ty = match ident_string(f)?.as_str() {
+        "ProgramState" => Ty::ProgramState(parse_program_state(&path)?),
+        "ProgramAccount" => Ty::ProgramAccount(parse_program_account(&path)?),
+        "CpiAccount" => Ty::CpiAccount(parse_cpi_account(&path)?),
+        "ZeroCopyAccount" => Ty::ZeroCopyAccount(parse_zero_copy_account(&path)?),
+        "Program" => Ty::Program(parse_program(&path)?),
+        "Signer" => Ty::Signer,
+        "Sysvar" => Ty::Sysvar(parse_sysvar(&path)?),
         "AccountInfo" => Ty::AccountInfo,
-        _ => panic!("invalid account type"),
+        _ => return Err(invalid_account_type(&f.ty)),
+    };
+    Ok(ty)
+}
+
+// Returns the name of the field's type, ignoring any module qualification,
+// e.g., `ProgramAccount` for `anchor_lang::ProgramAccount<'info, T>`.
+fn ident_string(f: &syn::Field) -> syn::Result<String> {
+    match &f.ty {
+        syn::Type::Path(ty_path) => match ty_path.path.segments.last() {
+            Some(segment) => Ok(segment.ident.to_string()),
+            None => Err(invalid_account_type(&f.ty)),
+        },
+        _ => Err(invalid_account_type(&f.ty)),
     }
 }
 
-fn ident_string(f: &syn::Field) -> String {
-    let path = match &f.ty {
-        syn::Type::Path(ty_path) => ty_path.path.clone(),
-        _ => panic!("invalid account syntax"),
-    };
-    // TODO: allow segmented paths.
-    assert!(path.segments.len() == 1);
-    let segments = &path.segments[0];
-    segments.ident.to_string()
+fn invalid_account_type(ty: &syn::Type) -> syn::Error {
+    syn::Error::new_spanned(
+        ty,
+        "invalid account type, expected an account container, e.g., \
+         `ProgramAccount<'info, T>`, or a struct deriving `Accounts`",
+    )
 }
 
-fn parse_program_state(path: &syn::Path) -> ProgramStateTy {
-    let account_ident = parse_account(&path);
-    ProgramStateTy { account_ident }
+fn parse_program_state(path: &syn::Path) -> syn::Result<ProgramStateTy> {
+    let account_type_path = parse_account(path)?;
+    Ok(ProgramStateTy { account_type_path })
 }
 
-fn parse_cpi_account(path: &syn::Path) -> CpiAccountTy {
-    let account_ident = parse_account(path);
-    CpiAccountTy { account_ident }
+fn parse_cpi_account(path: &syn::Path) -> syn::Result<CpiAccountTy> {
+    let account_type_path = parse_account(path)?;
+    Ok(CpiAccountTy { account_type_path })
 }
 
-fn parse_program_account(path: &syn::Path) -> ProgramAccountTy {
-    let account_ident = parse_account(path);
-    ProgramAccountTy { account_ident }
+fn parse_program_account(path: &syn::Path) -> syn::Result<ProgramAccountTy> {
+    let account_type_path = parse_account(path)?;
+    Ok(ProgramAccountTy { account_type_path })
 }
 
-fn parse_account(path: &syn::Path) -> syn::Ident {
-    let segments = &path.segments[0];
-    match &segments.arguments {
-        syn::PathArguments::AngleBracketed(args) => {
-            // Expected: <'info, MyType>.
-            assert!(args.args.len() == 2);
-            match &args.args[1] {
-                syn::GenericArgument::Type(syn::Type::Path(ty_path)) => {
-                    // TODO: allow segmented paths.
-                    assert!(ty_path.path.segments.len() == 1);
-                    let path_segment = &ty_path.path.segments[0];
-                    path_segment.ident.clone()
-                }
-                _ => panic!("Invalid ProgramAccount"),
-            }
-        }
-        _ => panic!("Invalid ProgramAccount"),
-    }
+fn parse_zero_copy_account(path: &syn::Path) -> syn::Result<ZeroCopyAccountTy> {
+    let account_type_path = parse_account(path)?;
+    Ok(ZeroCopyAccountTy { account_type_path })
 }
 
-fn parse_sysvar(path: &syn::Path) -> SysvarTy {
-    let segments = &path.segments[0];
-    let account_ident = match &segments.arguments {
-        syn::PathArguments::AngleBracketed(args) => {
-            // Expected: <'info, MyType>.
-            assert!(args.args.len() == 2);
-            match &args.args[1] {
-                syn::GenericArgument::Type(syn::Type::Path(ty_path)) => {
-                    // TODO: allow segmented paths.
-                    assert!(ty_path.path.segments.len() == 1);
-                    let path_segment = &ty_path.path.segments[0];
-                    path_segment.ident.clone()
-                }
-                _ => panic!("Invalid Sysvar"),
-            }
-        }
-        _ => panic!("Invalid Sysvar"),
+fn parse_program(path: &syn::Path) -> syn::Result<ProgramTy> {
+    let program_type_path = parse_account(path)?;
+    Ok(ProgramTy { program_type_path })
+}
+
+// Returns the type given to an account container, e.g., `MyType` or
+// `crate::state::MyType` for `ProgramAccount<'info, MyType>`.
+fn parse_account(path: &syn::Path) -> syn::Result<syn::TypePath> {
+    // Callers only pass paths whose last segment has been matched.
+    let segment = path.segments.last().unwrap();
+    let error = || {
+        syn::Error::new_spanned(
+            path,
+            format!(
+                "invalid {}, expected `{}<'info, T>`",
+                segment.ident, segment.ident
+            ),
+        )
     };
-    match account_ident.to_string().as_str() {
+    match &segment.arguments {
+        // Expected: <'info, MyType>.
+        syn::PathArguments::AngleBracketed(args) if args.args.len() == 2 => match &args.args[1] {
+            syn::GenericArgument::Type(syn::Type::Path(ty_path)) => Ok(ty_path.clone()),
+            _ => Err(error()),
+        },
+        _ => Err(error()),
+    }
+}
+
+fn parse_sysvar(path: &syn::Path) -> syn::Result<SysvarTy> {
+    let account_type_path = parse_account(path)?;
+    let account_ident = &account_type_path.path.segments.last().unwrap().ident;
+    let sysvar = match account_ident.to_string().as_str() {
         "Clock" => SysvarTy::Clock,
         "Rent" => SysvarTy::Rent,
         "EpochSchedule" => SysvarTy::EpochSchedule,
@@ -162,15 +280,28 @@ fn parse_sysvar(path: &syn::Path) -> SysvarTy {
         "StakeHistory" => SysvarTy::StakeHistory,
         "Instructions" => SysvarTy::Instructions,
         "Rewards" => SysvarTy::Rewards,
-        _ => panic!("Invalid Sysvar"),
-    }
+        _ => {
+            return Err(syn::Error::new_spanned(
+                account_type_path,
+                "invalid sysvar, expected one of: Clock, Rent, EpochSchedule, Fees, \
+                 RecentBlockhashes, SlotHashes, SlotHistory, StakeHistory, Instructions, \
+                 Rewards",
+            ))
+        }
+    };
+    Ok(sysvar)
 }
 
-fn parse_constraints(anchor: &syn::Attribute) -> (Vec<Constraint>, bool, bool, bool) {
+fn parse_constraints(anchor: &syn::Attribute) -> syn::Result<(Vec<Constraint>, bool, bool, bool)> {
     let mut tts = anchor.tokens.clone().into_iter();
-    let g_stream = match tts.next().expect("Must have a token group") {
-        proc_macro2::TokenTree::Group(g) => g.stream(),
-        _ => panic!("Invalid syntax"),
+    let g_stream = match tts.next() {
+        Some(TokenTree::Group(g)) => g.stream(),
+        _ => {
+            return Err(syn::Error::new_spanned(
+                anchor,
+                "expected a list of constraints, e.g., `#[account(mut)]`",
+            ))
+        }
     };
 
     let mut is_init = false;
@@ -178,11 +309,15 @@ fn parse_constraints(anchor: &syn::Attribute) -> (Vec<Constraint>, bool, bool, b
     let mut is_signer = false;
     let mut constraints = vec![];
     let mut is_rent_exempt = None;
+    let mut payer = None;
+    let mut space = None;
+    let mut bump = None;
+    let mut close = None;
 
-    let mut inner_tts = g_stream.into_iter();
+    let mut inner_tts = g_stream.into_iter().peekable();
     while let Some(token) = inner_tts.next() {
         match token {
-            proc_macro2::TokenTree::Ident(ident) => match ident.to_string().as_str() {
+            TokenTree::Ident(ident) => match ident.to_string().as_str() {
                 "init" => {
                     is_init = true;
                     is_mut = true;
@@ -200,93 +335,185 @@ fn parse_constraints(anchor: &syn::Attribute) -> (Vec<Constraint>, bool, bool, b
                     constraints.push(Constraint::Signer(ConstraintSigner {}));
                 }
                 "seeds" => {
-                    match inner_tts.next().unwrap() {
-                        proc_macro2::TokenTree::Punct(punct) => {
-                            assert!(punct.as_char() == '=');
-                            punct
+                    parse_eq(&mut inner_tts, &ident)?;
+                    let seeds = match inner_tts.next() {
+                        Some(TokenTree::Group(g)) => g,
+                        Some(t) => {
+                            return Err(syn::Error::new(
+                                t.span(),
+                                "expected a list of seeds, e.g., `seeds = [..]`",
+                            ))
+                        }
+                        None => {
+                            return Err(syn::Error::new(
+                                ident.span(),
+                                "expected a list of seeds, e.g., `seeds = [..]`",
+                            ))
                         }
-                        _ => panic!("invalid syntax"),
-                    };
-                    let seeds = match inner_tts.next().unwrap() {
-                        proc_macro2::TokenTree::Group(g) => g,
-                        _ => panic!("invalid syntax"),
                     };
-                    constraints.push(Constraint::Seeds(ConstraintSeeds { seeds }))
+                    constraints.push(Constraint::Seeds(ConstraintSeeds { seeds, bump: false }))
+                }
+                "bump" => {
+                    bump = Some(ident);
                 }
                 "belongs_to" | "has_one" => {
-                    match inner_tts.next().unwrap() {
-                        proc_macro2::TokenTree::Punct(punct) => {
-                            assert!(punct.as_char() == '=');
-                            punct
-                        }
-                        _ => panic!("invalid syntax"),
-                    };
-                    let join_target = match inner_tts.next().unwrap() {
-                        proc_macro2::TokenTree::Ident(ident) => ident,
-                        _ => panic!("invalid syntax"),
-                    };
+                    parse_eq(&mut inner_tts, &ident)?;
+                    let join_target = parse_ident(&mut inner_tts, &ident)?;
                     constraints.push(Constraint::BelongsTo(ConstraintBelongsTo { join_target }))
                 }
                 "owner" => {
-                    match inner_tts.next().unwrap() {
-                        proc_macro2::TokenTree::Punct(punct) => {
-                            assert!(punct.as_char() == '=');
-                            punct
-                        }
-                        _ => panic!("invalid syntax"),
-                    };
-                    let owner = match inner_tts.next().unwrap() {
-                        proc_macro2::TokenTree::Ident(ident) => ident,
-                        _ => panic!("invalid syntax"),
-                    };
+                    parse_eq(&mut inner_tts, &ident)?;
+                    let owner = parse_ident(&mut inner_tts, &ident)?;
                     let constraint = match owner.to_string().as_str() {
                         "program" => ConstraintOwner::Program,
                         "skip" => ConstraintOwner::Skip,
-                        _ => panic!("invalid syntax"),
+                        _ => {
+                            return Err(syn::Error::new(
+                                owner.span(),
+                                format!(
+                                    "invalid owner `{}`, expected one of: program, skip",
+                                    owner
+                                ),
+                            ))
+                        }
                     };
                     constraints.push(Constraint::Owner(constraint));
                 }
-                "rent_exempt" => {
-                    match inner_tts.next() {
-                        None => is_rent_exempt = Some(true),
-                        Some(tkn) => {
-                            match tkn {
-                                proc_macro2::TokenTree::Punct(punct) => {
-                                    assert!(punct.as_char() == '=');
-                                    punct
-                                }
-                                _ => panic!("invalid syntax"),
-                            };
-                            let should_skip = match inner_tts.next().unwrap() {
-                                proc_macro2::TokenTree::Ident(ident) => ident,
-                                _ => panic!("invalid syntax"),
-                            };
-                            match should_skip.to_string().as_str() {
-                                "skip" => {
-                                    is_rent_exempt = Some(false);
-                                },
-                                _ => panic!("invalid syntax: omit the rent_exempt attribute to enforce rent exemption"),
-                            };
-                        }
-                    };
+                "payer" => {
+                    parse_eq(&mut inner_tts, &ident)?;
+                    payer = Some((ident.clone(), parse_ident(&mut inner_tts, &ident)?));
+                }
+                "space" => {
+                    parse_eq(&mut inner_tts, &ident)?;
+                    space = Some((ident.clone(), parse_expr(&mut inner_tts, &ident)?));
+                }
+                "close" => {
+                    parse_eq(&mut inner_tts, &ident)?;
+                    close = Some((ident.clone(), parse_ident(&mut inner_tts, &ident)?));
                 }
+                "realloc" => {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        "`realloc` is unsupported: the Solana runtime targeted by this \
+                         version of anchor can't resize account data, so allocate the \
+                         maximum `space` needed upon `init`",
+                    ));
+                }
+                "rent_exempt" => match inner_tts.peek() {
+                    Some(TokenTree::Punct(punct)) if punct.as_char() == '=' => {
+                        parse_eq(&mut inner_tts, &ident)?;
+                        let should_skip = parse_ident(&mut inner_tts, &ident)?;
+                        match should_skip.to_string().as_str() {
+                            "skip" => {
+                                is_rent_exempt = Some(false);
+                            }
+                            _ => {
+                                return Err(syn::Error::new(
+                                    should_skip.span(),
+                                    "invalid rent_exempt value, expected `skip`: omit the \
+                                     rent_exempt attribute to enforce rent exemption",
+                                ))
+                            }
+                        };
+                    }
+                    _ => is_rent_exempt = Some(true),
+                },
                 _ => {
-                    panic!("invalid syntax");
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        format!(
+                            "invalid account constraint `{}`, expected one of: {}, or a \
+                             string literal",
+                            ident,
+                            CONSTRAINT_KEYWORDS.join(", "),
+                        ),
+                    ));
                 }
             },
-            proc_macro2::TokenTree::Punct(punct) => {
+            TokenTree::Punct(punct) => {
                 if punct.as_char() != ',' {
-                    panic!("invalid syntax");
+                    return Err(syn::Error::new(
+                        punct.span(),
+                        format!("unexpected `{}`, expected `,`", punct.as_char()),
+                    ));
                 }
             }
-            proc_macro2::TokenTree::Literal(literal) => {
+            TokenTree::Literal(literal) => {
                 let tokens: proc_macro2::TokenStream =
-                    literal.to_string().replace("\"", "").parse().unwrap();
+                    literal.to_string().replace("\"", "").parse().map_err(|_| {
+                        syn::Error::new(literal.span(), "invalid literal constraint expression")
+                    })?;
                 constraints.push(Constraint::Literal(ConstraintLiteral { tokens }));
             }
-            _ => {
-                panic!("invalid syntax");
+            TokenTree::Group(group) => {
+                return Err(syn::Error::new(
+                    group.span(),
+                    format!(
+                        "unexpected group, expected one of: {}, or a string literal",
+                        CONSTRAINT_KEYWORDS.join(", "),
+                    ),
+                ));
+            }
+        }
+    }
+
+    if let Some(bump) = bump {
+        let seeds = constraints.iter_mut().find_map(|c| match c {
+            Constraint::Seeds(c) => Some(c),
+            _ => None,
+        });
+        match seeds {
+            None => {
+                return Err(syn::Error::new(
+                    bump.span(),
+                    "`bump` requires the seeds of the program derived address, e.g., \
+                     `seeds = [..], bump`",
+                ))
+            }
+            Some(seeds) => seeds.bump = true,
+        }
+    }
+
+    if let Some((keyword, sol_dest)) = close {
+        if !is_mut {
+            return Err(syn::Error::new(
+                keyword.span(),
+                "`close` requires the account to be mutable, e.g., `mut, close = authority`",
+            ));
+        }
+        constraints.push(Constraint::Close(ConstraintClose { sol_dest }));
+    }
+
+    match (payer, space) {
+        (None, None) => {}
+        (Some((_, payer)), Some((_, space))) => {
+            if !is_init {
+                return Err(syn::Error::new(
+                    payer.span(),
+                    "`payer` and `space` can only be used with `init`",
+                ));
+            }
+            // Unless it's a program derived address, the account being
+            // created must sign the transaction.
+            if !constraints
+                .iter()
+                .any(|c| matches!(c, Constraint::Seeds(_)))
+            {
+                is_signer = true;
             }
+            constraints.push(Constraint::Init(ConstraintInit { payer, space }));
+        }
+        (Some((keyword, _)), None) => {
+            return Err(syn::Error::new(
+                keyword.span(),
+                "`payer` requires the number of bytes to allocate, e.g., `space = 8 + 32`",
+            ))
+        }
+        (None, Some((keyword, _))) => {
+            return Err(syn::Error::new(
+                keyword.span(),
+                "`space` requires the account funding the allocation, e.g., `payer = authority`",
+            ))
         }
     }
 
@@ -297,5 +524,65 @@ fn parse_constraints(anchor: &syn::Attribute) -> (Vec<Constraint>, bool, bool, b
         }
     }
 
-    (constraints, is_mut, is_signer, is_init)
+    Ok((constraints, is_mut, is_signer, is_init))
+}
+
+// Consumes the `=` following the given constraint `keyword`.
+fn parse_eq(
+    tts: &mut Peekable<impl Iterator<Item = TokenTree>>,
+    keyword: &proc_macro2::Ident,
+) -> syn::Result<()> {
+    match tts.next() {
+        Some(TokenTree::Punct(punct)) if punct.as_char() == '=' => Ok(()),
+        Some(t) => Err(syn::Error::new(
+            t.span(),
+            format!("expected `=` after `{}`", keyword),
+        )),
+        None => Err(syn::Error::new(
+            keyword.span(),
+            format!("expected `=` after `{}`", keyword),
+        )),
+    }
+}
+
+// Consumes the identifier assigned to the given constraint `keyword`.
+fn parse_ident(
+    tts: &mut Peekable<impl Iterator<Item = TokenTree>>,
+    keyword: &proc_macro2::Ident,
+) -> syn::Result<proc_macro2::Ident> {
+    match tts.next() {
+        Some(TokenTree::Ident(ident)) => Ok(ident),
+        Some(t) => Err(syn::Error::new(
+            t.span(),
+            format!("expected an identifier after `{} =`", keyword),
+        )),
+        None => Err(syn::Error::new(
+            keyword.span(),
+            format!("expected an identifier after `{} =`", keyword),
+        )),
+    }
+}
+
+// Consumes the expression assigned to the given constraint `keyword`, i.e.,
+// all tokens up to the next top level `,`.
+fn parse_expr(
+    tts: &mut Peekable<impl Iterator<Item = TokenTree>>,
+    keyword: &proc_macro2::Ident,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let mut expr = proc_macro2::TokenStream::new();
+    while let Some(t) = tts.peek() {
+        if let TokenTree::Punct(punct) = t {
+            if punct.as_char() == ',' {
+                break;
+            }
+        }
+        expr.extend(tts.next());
+    }
+    if expr.is_empty() {
+        return Err(syn::Error::new(
+            keyword.span(),
+            format!("expected an expression after `{} =`", keyword),
+        ));
+    }
+    Ok(expr)
 }
@@ -1,6 +1,8 @@
+use crate::parser;
 use crate::{Error, ErrorCode};
 
-// Removes any internal #[msg] attributes, as they are inert.
+// Removes any internal #[msg] attributes, as they are inert. Doc comments are
+// kept.
 pub fn parse(error_enum: &mut syn::ItemEnum) -> Error {
     let ident = error_enum.ident.clone();
     let mut last_discriminant = 0;
@@ -9,6 +11,7 @@ pub fn parse(error_enum: &mut syn::ItemEnum) -> Error {
         .iter_mut()
         .map(|variant: &mut syn::Variant| {
             let msg = parse_error_attribute(variant);
+            let docs = parser::docs(&variant.attrs);
             let ident = variant.ident.clone();
             let id = match &variant.discriminant {
                 None => last_discriminant,
@@ -24,10 +27,15 @@ pub fn parse(error_enum: &mut syn::ItemEnum) -> Error {
             };
             last_discriminant = id + 1;
 
-            // Remove any attributes on the error variant.
-            variant.attrs = vec![];
+            // Remove any attributes on the error variant, other than docs.
+            variant.attrs.retain(|attr| attr.path.is_ident("doc"));
 
-            ErrorCode { id, ident, msg }
+            ErrorCode {
+                id,
+                ident,
+                msg,
+                docs,
+            }
         })
         .collect();
 
@@ -40,7 +48,11 @@ pub fn parse(error_enum: &mut syn::ItemEnum) -> Error {
 }
 
 fn parse_error_attribute(variant: &syn::Variant) -> Option<String> {
-    let attrs = &variant.attrs;
+    let attrs: Vec<&syn::Attribute> = variant
+        .attrs
+        .iter()
+        .filter(|attr| !attr.path.is_ident("doc"))
+        .collect();
     match attrs.len() {
         0 => None,
         1 => {
@@ -1,28 +1,23 @@
 use crate::idl::*;
 use crate::parser::{self, accounts, error, program};
-use crate::{AccountsStruct, StateIx};
-use anyhow::Result;
+use crate::{AccountsStruct, Error, Program, StateIx, FRAMEWORK_ERROR_CODES};
+use anyhow::{anyhow, Result};
 use heck::MixedCase;
-use quote::ToTokens;
 use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
 use std::fs::File;
 use std::io::Read;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 const DERIVE_NAME: &str = "Accounts";
 
-// Parse an entire interface file.
+// Parse an entire interface file, along with every module it declares.
 pub fn parse(filename: impl AsRef<Path>) -> Result<Idl> {
-    let mut file = File::open(&filename)?;
-
-    let mut src = String::new();
-    file.read_to_string(&mut src).expect("Unable to read file");
-
-    let f = syn::parse_file(&src).expect("Unable to parse file");
+    let f = parse_crate(filename.as_ref())?;
 
     let p = program::parse(parse_program_mod(&f));
 
-    let accs = parse_accounts(&f);
+    let accs = parse_accounts(&f)?;
     let acc_names = {
         let mut acc_names = HashSet::new();
         for accs_strct in accs.values() {
@@ -33,13 +28,70 @@ pub fn parse(filename: impl AsRef<Path>) -> Result<Idl> {
         acc_names
     };
 
-    let state = match p.state {
+    // todo: don't unwrap
+    let idl_accounts = |anchor_ident: &syn::Ident| {
+        accs.get(&anchor_ident.to_string())
+            .unwrap()
+            .idl_accounts(&accs)
+    };
+    let state = parse_state(&p, idl_accounts);
+    let instructions = parse_ixs(&p, idl_accounts);
+
+    let error = parse_error_enum(&f).map(|mut e| error::parse(&mut e));
+    let error_codes = Some(idl_error_codes(error.as_ref()));
+
+    // All user defined types.
+    let mut accounts = vec![];
+    let mut types = vec![];
+    let ty_defs = parse_ty_defs(&f)?;
+
+    let error_name = error.map(|e| e.name).unwrap_or_default();
+
+    for ty_def in ty_defs {
+        // Don't add the error type to the types or accounts sections.
+        if ty_def.name != error_name {
+            if acc_names.contains(&ty_def.name) {
+                accounts.push(ty_def);
+            } else {
+                types.push(ty_def);
+            }
+        }
+    }
+
+    let events = parse_events(&f)?;
+    let metadata = parse_program_id(&f).map(|address| serde_json::json!({ "address": address }));
+
+    Ok(Idl {
+        version: "0.0.0".to_string(),
+        name: p.name.to_string(),
+        state,
+        instructions,
+        types,
+        events: if events.is_empty() {
+            None
+        } else {
+            Some(events)
+        },
+        accounts,
+        errors: error_codes,
+        metadata,
+    })
+}
+
+// Parses the state struct and methods of the program, with the accounts of
+// each method given by `idl_accounts` for its `Accounts` struct.
+pub fn parse_state(
+    p: &Program,
+    idl_accounts: impl Fn(&syn::Ident) -> Vec<IdlAccountItem>,
+) -> Option<IdlState> {
+    match &p.state {
         None => None,
-        Some(state) => match state.ctor_and_anchor {
+        Some(state) => match &state.ctor_and_anchor {
             None => None, // State struct defined but no implementation
             Some((ctor, anchor_ident)) => {
                 let mut methods = state
                     .impl_block_and_methods
+                    .as_ref()
                     .map(|(_impl_block, methods)| {
                         methods
                             .iter()
@@ -49,20 +101,18 @@ pub fn parse(filename: impl AsRef<Path>) -> Result<Idl> {
                                     .args
                                     .iter()
                                     .map(|arg| {
-                                        let mut tts = proc_macro2::TokenStream::new();
-                                        arg.raw_arg.ty.to_tokens(&mut tts);
-                                        let ty = tts.to_string().parse().unwrap();
+                                        let ty = to_idl_type(&arg.raw_arg.ty).unwrap();
                                         IdlField {
                                             name: arg.name.to_string().to_mixed_case(),
                                             ty,
+                                            docs: None,
                                         }
                                     })
                                     .collect::<Vec<_>>();
-                                let accounts_strct =
-                                    accs.get(&method.anchor_ident.to_string()).unwrap();
-                                let accounts = accounts_strct.idl_accounts(&accs);
+                                let accounts = idl_accounts(&method.anchor_ident);
                                 IdlStateMethod {
                                     name,
+                                    docs: parser::docs(&method.raw_method.attrs),
                                     args,
                                     accounts,
                                 }
@@ -87,21 +137,20 @@ pub fn parse(filename: impl AsRef<Path>) -> Result<Idl> {
                         })
                         .map(|arg: &syn::FnArg| match arg {
                             syn::FnArg::Typed(arg_typed) => {
-                                let mut tts = proc_macro2::TokenStream::new();
-                                arg_typed.ty.to_tokens(&mut tts);
-                                let ty = tts.to_string().parse().unwrap();
+                                let ty = to_idl_type(&arg_typed.ty).unwrap();
                                 IdlField {
                                     name: parser::tts_to_string(&arg_typed.pat).to_mixed_case(),
                                     ty,
+                                    docs: None,
                                 }
                             }
                             _ => panic!("Invalid syntax"),
                         })
                         .collect();
-                    let accounts_strct = accs.get(&anchor_ident.to_string()).unwrap();
-                    let accounts = accounts_strct.idl_accounts(&accs);
+                    let accounts = idl_accounts(anchor_ident);
                     IdlStateMethod {
                         name,
+                        docs: parser::docs(&ctor.attrs),
                         args,
                         accounts,
                     }
@@ -110,100 +159,298 @@ pub fn parse(filename: impl AsRef<Path>) -> Result<Idl> {
                 methods.insert(0, ctor);
 
                 let strct = {
-                    let fields = match state.strct.fields {
+                    let fields = match &state.strct.fields {
                         syn::Fields::Named(f_named) => f_named
                             .named
                             .iter()
                             .map(|f: &syn::Field| {
-                                let mut tts = proc_macro2::TokenStream::new();
-                                f.ty.to_tokens(&mut tts);
-                                let ty = tts.to_string().parse().unwrap();
+                                let ty = to_idl_type(&f.ty).unwrap();
                                 IdlField {
                                     name: f.ident.as_ref().unwrap().to_string().to_mixed_case(),
                                     ty,
+                                    docs: parser::docs(&f.attrs),
                                 }
                             })
                             .collect::<Vec<IdlField>>(),
                         _ => panic!("State must be a struct"),
                     };
                     IdlTypeDef {
-                        name: state.name,
-                        ty: IdlTypeDefTy::Struct { fields },
+                        name: state.name.clone(),
+                        docs: parser::docs(&state.strct.attrs),
+                        ty: IdlTypeDefTy::Struct {
+                            generics: vec![],
+                            fields,
+                        },
+                        zero_copy: false,
                     }
                 };
 
                 Some(IdlState { strct, methods })
             }
         },
-    };
-    let error = parse_error_enum(&f).map(|mut e| error::parse(&mut e));
-    let error_codes = error.as_ref().map(|e| {
-        e.codes
-            .iter()
-            .map(|code| IdlErrorCode {
-                code: 100 + code.id,
-                name: code.ident.to_string(),
-                msg: code.msg.clone(),
-            })
-            .collect::<Vec<IdlErrorCode>>()
-    });
+    }
+}
 
-    let instructions = p
-        .ixs
+// Parses the instructions of the program, with the accounts of each given by
+// `idl_accounts` for its `Accounts` struct.
+pub fn parse_ixs(
+    p: &Program,
+    idl_accounts: impl Fn(&syn::Ident) -> Vec<IdlAccountItem>,
+) -> Vec<IdlIx> {
+    p.ixs
         .iter()
         .map(|ix| {
             let args = ix
                 .args
                 .iter()
                 .map(|arg| {
-                    let mut tts = proc_macro2::TokenStream::new();
-                    arg.raw_arg.ty.to_tokens(&mut tts);
-                    let ty = tts.to_string().parse().unwrap();
+                    let ty = to_idl_type(&arg.raw_arg.ty).unwrap();
                     IdlField {
                         name: arg.name.to_string().to_mixed_case(),
                         ty,
+                        docs: None,
                     }
                 })
                 .collect::<Vec<_>>();
-            // todo: don't unwrap
-            let accounts_strct = accs.get(&ix.anchor_ident.to_string()).unwrap();
-            let accounts = accounts_strct.idl_accounts(&accs);
+            let accounts = idl_accounts(&ix.anchor_ident);
             IdlIx {
                 name: ix.ident.to_string().to_mixed_case(),
+                docs: parser::docs(&ix.raw_method.attrs),
                 accounts,
                 args,
             }
         })
-        .collect::<Vec<_>>();
+        .collect::<Vec<_>>()
+}
 
-    // All user defined types.
-    let mut accounts = vec![];
-    let mut types = vec![];
-    let ty_defs = parse_ty_defs(&f)?;
+// Framework errors first, so that clients can decode failed constraints,
+// followed by the user defined errors, if any.
+pub fn idl_error_codes(error: Option<&Error>) -> Vec<IdlErrorCode> {
+    let mut error_codes = FRAMEWORK_ERROR_CODES
+        .iter()
+        .map(|(code, name, msg)| IdlErrorCode {
+            code: *code,
+            name: name.to_string(),
+            msg: Some(msg.to_string()),
+            docs: None,
+        })
+        .collect::<Vec<IdlErrorCode>>();
+    if let Some(e) = error {
+        error_codes.extend(idl_user_error_codes(e));
+    }
+    error_codes
+}
 
-    let error_name = error.map(|e| e.name).unwrap_or_else(|| "".to_string());
+// User defined errors are offset by 100, as codes below are reserved for the
+// framework.
+pub fn idl_user_error_codes(error: &Error) -> Vec<IdlErrorCode> {
+    error
+        .codes
+        .iter()
+        .map(|code| IdlErrorCode {
+            code: 100 + code.id,
+            name: code.ident.to_string(),
+            msg: code.msg.clone(),
+            docs: code.docs.clone(),
+        })
+        .collect()
+}
 
-    for ty_def in ty_defs {
-        // Don't add the error type to the types or accounts sections.
-        if ty_def.name != error_name {
-            if acc_names.contains(&ty_def.name) {
-                accounts.push(ty_def);
-            } else {
-                types.push(ty_def);
+// Parses the crate rooted at `filename` into a single file, flattening the
+// items of every module, inline or declared with `mod foo;`, into the root so
+// that they're all visible to the IDL. The `#[program]` mod is kept intact.
+fn parse_crate(filename: &Path) -> Result<syn::File> {
+    let mut f = parse_file(filename)?;
+    let dir = filename.parent().unwrap_or_else(|| Path::new("."));
+    let items = std::mem::take(&mut f.items);
+    f.items = resolve_array_lens(flatten_items(items, dir)?)?;
+    check_duplicate_names(&f.items)?;
+    Ok(f)
+}
+
+// Types from all modules share the IDL's namespace, so a name defined by more
+// than one module would silently describe only one of the definitions.
+fn check_duplicate_names(items: &[syn::Item]) -> Result<()> {
+    let mut names = HashSet::new();
+    for item in items {
+        let name = match item {
+            syn::Item::Struct(item_strct) => {
+                let is_accounts = item_strct
+                    .attrs
+                    .iter()
+                    .any(|attr| attr.tokens.to_string().contains(DERIVE_NAME));
+                let is_pub = matches!(item_strct.vis, syn::Visibility::Public(_));
+                if !is_accounts && !is_pub && !is_event(item_strct) {
+                    continue;
+                }
+                &item_strct.ident
             }
+            syn::Item::Enum(item_enum) => &item_enum.ident,
+            _ => continue,
+        };
+        if !names.insert(name.to_string()) {
+            return Err(anyhow!(
+                "`{}` is defined by more than one module, but types from all modules share \
+                 the IDL's namespace, so rename one of them",
+                name
+            ));
         }
     }
+    Ok(())
+}
 
-    Ok(Idl {
-        version: "0.0.0".to_string(),
-        name: p.name.to_string(),
-        state,
-        instructions,
-        types,
-        accounts,
-        errors: error_codes,
-        metadata: None,
-    })
+// Replaces the integer constants used as array lengths, e.g., `MAX_LEN` in
+// `[u8; MAX_LEN]`, with their values, so that the IDL can describe the arrays.
+fn resolve_array_lens(items: Vec<syn::Item>) -> Result<Vec<syn::Item>> {
+    let consts: HashMap<String, proc_macro2::Literal> = items
+        .iter()
+        .filter_map(|item| match item {
+            syn::Item::Const(item_const) => match &*item_const.expr {
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Int(len),
+                    ..
+                }) => {
+                    let len = len.base10_parse::<usize>().ok()?;
+                    Some((
+                        item_const.ident.to_string(),
+                        proc_macro2::Literal::usize_unsuffixed(len),
+                    ))
+                }
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect();
+    if consts.is_empty() {
+        return Ok(items);
+    }
+    items
+        .into_iter()
+        .map(|item| {
+            let tokens = replace_array_lens(quote::ToTokens::into_token_stream(item), &consts);
+            syn::parse2(tokens).map_err(|e| anyhow!("Unable to resolve array lengths: {}", e))
+        })
+        .collect()
+}
+
+fn replace_array_lens(
+    tokens: proc_macro2::TokenStream,
+    consts: &HashMap<String, proc_macro2::Literal>,
+) -> proc_macro2::TokenStream {
+    use proc_macro2::{Delimiter, Group, TokenTree};
+    tokens
+        .into_iter()
+        .map(|tt| match tt {
+            TokenTree::Group(group) => {
+                let mut stream: Vec<TokenTree> = replace_array_lens(group.stream(), consts)
+                    .into_iter()
+                    .collect();
+                // An array type, or repeat expression, ending in `; <const>`.
+                if group.delimiter() == Delimiter::Bracket {
+                    if let [.., TokenTree::Punct(semi), TokenTree::Ident(len)] = stream.as_slice() {
+                        if let (';', Some(value)) = (semi.as_char(), consts.get(&len.to_string())) {
+                            let mut value = value.clone();
+                            value.set_span(len.span());
+                            *stream.last_mut().unwrap() = TokenTree::Literal(value);
+                        }
+                    }
+                }
+                let mut resolved = Group::new(group.delimiter(), stream.into_iter().collect());
+                resolved.set_span(group.span());
+                TokenTree::Group(resolved)
+            }
+            tt => tt,
+        })
+        .collect()
+}
+
+fn parse_file(filename: &Path) -> Result<syn::File> {
+    let mut file = File::open(filename)
+        .map_err(|e| anyhow!("Unable to open {}: {}", filename.display(), e))?;
+    let mut src = String::new();
+    file.read_to_string(&mut src)?;
+    syn::parse_file(&src).map_err(|e| anyhow!("Unable to parse {}: {}", filename.display(), e))
+}
+
+// `dir` is the directory containing the files of any modules declared by
+// `items`.
+fn flatten_items(items: Vec<syn::Item>, dir: &Path) -> Result<Vec<syn::Item>> {
+    let mut flattened = vec![];
+    for item in items {
+        match item {
+            syn::Item::Mod(item_mod) if !is_program_mod(&item_mod) => {
+                if is_test_mod(&item_mod) {
+                    continue;
+                }
+                match item_mod.content {
+                    Some((_, items)) => {
+                        let mod_dir = dir.join(item_mod.ident.to_string());
+                        flattened.extend(flatten_items(items, &mod_dir)?);
+                    }
+                    None => {
+                        let (mod_file, mod_dir) = find_mod_file(&item_mod, dir)?;
+                        let f = parse_file(&mod_file)?;
+                        flattened.extend(flatten_items(f.items, &mod_dir)?);
+                    }
+                }
+            }
+            _ => flattened.push(item),
+        }
+    }
+    Ok(flattened)
+}
+
+// Returns the file defining the module declared by `mod foo;`, i.e.,
+// `foo.rs` or `foo/mod.rs` (or the file given by a `#[path]` attribute), along
+// with the directory containing the files of its own submodules.
+fn find_mod_file(item_mod: &syn::ItemMod, dir: &Path) -> Result<(PathBuf, PathBuf)> {
+    let name = item_mod.ident.to_string();
+    if let Some(attr) = item_mod
+        .attrs
+        .iter()
+        .find(|attr| attr.path.is_ident("path"))
+    {
+        if let Ok(syn::Meta::NameValue(syn::MetaNameValue {
+            lit: syn::Lit::Str(path),
+            ..
+        })) = attr.parse_meta()
+        {
+            let mod_file = dir.join(path.value());
+            let mod_dir = mod_file.parent().unwrap_or(dir).to_path_buf();
+            return Ok((mod_file, mod_dir));
+        }
+    }
+    let mod_file = dir.join(format!("{}.rs", name));
+    if mod_file.exists() {
+        return Ok((mod_file, dir.join(&name)));
+    }
+    let mod_file = dir.join(&name).join("mod.rs");
+    if mod_file.exists() {
+        return Ok((mod_file, dir.join(&name)));
+    }
+    Err(anyhow!(
+        "Unable to find module `{}`: expected {}.rs or {}/mod.rs in {}",
+        name,
+        name,
+        name,
+        dir.display()
+    ))
+}
+
+fn is_program_mod(item_mod: &syn::ItemMod) -> bool {
+    item_mod
+        .attrs
+        .iter()
+        .any(|attr| attr.path.segments.last().unwrap().ident == "program")
+}
+
+// Modules compiled only for tests, i.e., `#[cfg(test)]`, aren't part of the
+// program's interface.
+fn is_test_mod(item_mod: &syn::ItemMod) -> bool {
+    item_mod
+        .attrs
+        .iter()
+        .any(|attr| attr.path.is_ident("cfg") && parser::tts_to_string(&attr.tokens) == "(test)")
 }
 
 // Parse the main program mod.
@@ -212,17 +459,7 @@ fn parse_program_mod(f: &syn::File) -> syn::ItemMod {
         .items
         .iter()
         .filter_map(|i| match i {
-            syn::Item::Mod(item_mod) => {
-                let mod_count = item_mod
-                    .attrs
-                    .iter()
-                    .filter(|attr| attr.path.segments.last().unwrap().ident == "program")
-                    .count();
-                if mod_count != 1 {
-                    return None;
-                }
-                Some(item_mod)
-            }
+            syn::Item::Mod(item_mod) if is_program_mod(item_mod) => Some(item_mod),
             _ => None,
         })
         .collect::<Vec<_>>();
@@ -232,6 +469,19 @@ fn parse_program_mod(f: &syn::File) -> syn::ItemMod {
     mods[0].clone()
 }
 
+// Parse the program id given to `declare_id!`, if any.
+fn parse_program_id(f: &syn::File) -> Option<String> {
+    f.items.iter().find_map(|i| match i {
+        syn::Item::Macro(item_macro)
+            if item_macro.mac.path.segments.last().unwrap().ident == "declare_id" =>
+        {
+            let id: syn::LitStr = item_macro.mac.parse_body().ok()?;
+            Some(id.value())
+        }
+        _ => None,
+    })
+}
+
 fn parse_error_enum(f: &syn::File) -> Option<syn::ItemEnum> {
     f.items
         .iter()
@@ -257,26 +507,72 @@ fn parse_error_enum(f: &syn::File) -> Option<syn::ItemEnum> {
         .cloned()
 }
 // Parse all structs implementing the `Accounts` trait.
-fn parse_accounts(f: &syn::File) -> HashMap<String, AccountsStruct> {
+fn parse_accounts(f: &syn::File) -> Result<HashMap<String, AccountsStruct>> {
+    let mut accs = HashMap::new();
+    for i in &f.items {
+        // TODO: parse manual implementations. Currently we only look
+        //       for derives.
+        if let syn::Item::Struct(i_strct) = i {
+            for attr in &i_strct.attrs {
+                if attr.tokens.to_string().contains(DERIVE_NAME) {
+                    let strct = accounts::parse(i_strct)?;
+                    accs.insert(strct.ident.to_string(), strct);
+                    break;
+                }
+            }
+        }
+    }
+    Ok(accs)
+}
+
+// Parse all structs marked with the `#[event]` attribute.
+fn parse_events(f: &syn::File) -> Result<Vec<IdlEvent>> {
     f.items
         .iter()
         .filter_map(|i| match i {
-            syn::Item::Struct(i_strct) => {
-                for attr in &i_strct.attrs {
-                    if attr.tokens.to_string().contains(DERIVE_NAME) {
-                        let strct = accounts::parse(i_strct);
-                        return Some((strct.ident.to_string(), strct));
-                    }
-                }
-                None
-            }
-            // TODO: parse manual implementations. Currently we only look
-            //       for derives.
+            syn::Item::Struct(item_strct) if is_event(item_strct) => Some(item_strct),
             _ => None,
         })
+        .map(parse_event)
         .collect()
 }
 
+pub fn parse_event(item_strct: &syn::ItemStruct) -> Result<IdlEvent> {
+    let fields = match &item_strct.fields {
+        syn::Fields::Named(fields) => fields
+            .named
+            .iter()
+            .map(|f: &syn::Field| {
+                Ok(IdlField {
+                    name: f.ident.as_ref().unwrap().to_string().to_mixed_case(),
+                    ty: to_idl_type(&f.ty)?,
+                    docs: parser::docs(&f.attrs),
+                })
+            })
+            .collect::<Result<Vec<IdlField>>>()?,
+        _ => panic!("Only named structs are allowed for events."),
+    };
+    Ok(IdlEvent {
+        name: item_strct.ident.to_string(),
+        fields,
+    })
+}
+
+fn is_event(item_strct: &syn::ItemStruct) -> bool {
+    item_strct
+        .attrs
+        .iter()
+        .any(|attr| attr.path.segments.last().unwrap().ident == "event")
+}
+
+// True for structs marked `#[account(zero_copy)]`.
+fn is_zero_copy(item_strct: &syn::ItemStruct) -> bool {
+    item_strct.attrs.iter().any(|attr| {
+        attr.path.segments.last().unwrap().ident == "account"
+            && attr.tokens.to_string().contains("zero_copy")
+    })
+}
+
 // Parse all user defined types in the file.
 fn parse_ty_defs(f: &syn::File) -> Result<Vec<IdlTypeDef>> {
     f.items
@@ -288,73 +584,110 @@ fn parse_ty_defs(f: &syn::File) -> Result<Vec<IdlTypeDef>> {
                         return None;
                     }
                 }
+                // Events have their own section in the IDL.
+                if is_event(item_strct) {
+                    return None;
+                }
                 if let syn::Visibility::Public(_) = &item_strct.vis {
-                    let name = item_strct.ident.to_string();
-                    let fields = match &item_strct.fields {
-                        syn::Fields::Named(fields) => fields
-                            .named
-                            .iter()
-                            .map(|f: &syn::Field| {
-                                let mut tts = proc_macro2::TokenStream::new();
-                                f.ty.to_tokens(&mut tts);
-                                Ok(IdlField {
-                                    name: f.ident.as_ref().unwrap().to_string().to_mixed_case(),
-                                    ty: tts.to_string().parse()?,
-                                })
-                            })
-                            .collect::<Result<Vec<IdlField>>>(),
-                        _ => panic!("Only named structs are allowed."),
-                    };
-
-                    return Some(fields.map(|fields| IdlTypeDef {
-                        name,
-                        ty: IdlTypeDefTy::Struct { fields },
-                    }));
+                    return Some(parse_struct_ty_def(item_strct));
                 }
                 None
             }
-            syn::Item::Enum(enm) => {
-                let name = enm.ident.to_string();
-                let variants = enm
-                    .variants
-                    .iter()
-                    .map(|variant: &syn::Variant| {
-                        let name = variant.ident.to_string();
-                        let fields = match &variant.fields {
-                            syn::Fields::Unit => None,
-                            syn::Fields::Unnamed(fields) => {
-                                let fields: Vec<IdlType> =
-                                    fields.unnamed.iter().map(to_idl_type).collect();
-                                Some(EnumFields::Tuple(fields))
-                            }
-                            syn::Fields::Named(fields) => {
-                                let fields: Vec<IdlField> = fields
-                                    .named
-                                    .iter()
-                                    .map(|f: &syn::Field| {
-                                        let name = f.ident.as_ref().unwrap().to_string();
-                                        let ty = to_idl_type(f);
-                                        IdlField { name, ty }
-                                    })
-                                    .collect();
-                                Some(EnumFields::Named(fields))
-                            }
-                        };
-                        EnumVariant { name, fields }
-                    })
-                    .collect::<Vec<EnumVariant>>();
-                Some(Ok(IdlTypeDef {
-                    name,
-                    ty: IdlTypeDefTy::Enum { variants },
-                }))
-            }
+            syn::Item::Enum(enm) => Some(parse_enum_ty_def(enm)),
             _ => None,
         })
         .collect()
 }
 
-fn to_idl_type(f: &syn::Field) -> IdlType {
-    let mut tts = proc_macro2::TokenStream::new();
-    f.ty.to_tokens(&mut tts);
-    tts.to_string().parse().unwrap()
+pub fn parse_struct_ty_def(item_strct: &syn::ItemStruct) -> Result<IdlTypeDef> {
+    let generics = to_idl_generics(&item_strct.generics);
+    let ty = match &item_strct.fields {
+        syn::Fields::Named(fields) => IdlTypeDefTy::Struct {
+            fields: fields
+                .named
+                .iter()
+                .map(|f: &syn::Field| {
+                    Ok(IdlField {
+                        name: f.ident.as_ref().unwrap().to_string().to_mixed_case(),
+                        ty: IdlType::from_generic_type(&f.ty, &generics)?,
+                        docs: parser::docs(&f.attrs),
+                    })
+                })
+                .collect::<Result<Vec<IdlField>>>()?,
+            generics,
+        },
+        syn::Fields::Unnamed(fields) => IdlTypeDefTy::Tuple {
+            fields: fields
+                .unnamed
+                .iter()
+                .map(|f: &syn::Field| IdlType::from_generic_type(&f.ty, &generics))
+                .collect::<Result<Vec<IdlType>>>()?,
+            generics,
+        },
+        syn::Fields::Unit => IdlTypeDefTy::Struct {
+            generics,
+            fields: vec![],
+        },
+    };
+    Ok(IdlTypeDef {
+        name: item_strct.ident.to_string(),
+        docs: parser::docs(&item_strct.attrs),
+        ty,
+        zero_copy: is_zero_copy(item_strct),
+    })
+}
+
+pub fn parse_enum_ty_def(enm: &syn::ItemEnum) -> Result<IdlTypeDef> {
+    let generics = to_idl_generics(&enm.generics);
+    let variants = enm
+        .variants
+        .iter()
+        .map(|variant: &syn::Variant| {
+            let name = variant.ident.to_string();
+            let fields = match &variant.fields {
+                syn::Fields::Unit => None,
+                syn::Fields::Unnamed(fields) => {
+                    let fields = fields
+                        .unnamed
+                        .iter()
+                        .map(|f: &syn::Field| IdlType::from_generic_type(&f.ty, &generics))
+                        .collect::<Result<Vec<IdlType>>>()?;
+                    Some(EnumFields::Tuple(fields))
+                }
+                syn::Fields::Named(fields) => {
+                    let fields = fields
+                        .named
+                        .iter()
+                        .map(|f: &syn::Field| {
+                            let name = f.ident.as_ref().unwrap().to_string();
+                            let ty = IdlType::from_generic_type(&f.ty, &generics)?;
+                            let docs = parser::docs(&f.attrs);
+                            Ok(IdlField { name, ty, docs })
+                        })
+                        .collect::<Result<Vec<IdlField>>>()?;
+                    Some(EnumFields::Named(fields))
+                }
+            };
+            Ok(EnumVariant { name, fields })
+        })
+        .collect::<Result<Vec<EnumVariant>>>()?;
+    Ok(IdlTypeDef {
+        name: enm.ident.to_string(),
+        docs: parser::docs(&enm.attrs),
+        ty: IdlTypeDefTy::Enum { generics, variants },
+        zero_copy: false,
+    })
+}
+
+fn to_idl_type(ty: &syn::Type) -> Result<IdlType> {
+    IdlType::try_from(ty)
+}
+
+// Names of the type parameters of a type definition. Lifetimes aren't
+// serialized and so are omitted.
+fn to_idl_generics(generics: &syn::Generics) -> Vec<String> {
+    generics
+        .type_params()
+        .map(|param| param.ident.to_string())
+        .collect()
 }
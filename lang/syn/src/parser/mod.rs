@@ -4,6 +4,30 @@ pub mod error;
 pub mod file;
 pub mod program;
 
+// Returns the lines of an item's `///` doc comments, i.e., its
+// `#[doc = "..."]` attributes, if any.
+pub fn docs(attrs: &[syn::Attribute]) -> Option<Vec<String>> {
+    let docs: Vec<String> = attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("doc"))
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(syn::Meta::NameValue(syn::MetaNameValue {
+                lit: syn::Lit::Str(doc),
+                ..
+            })) => {
+                let doc = doc.value();
+                Some(doc.strip_prefix(' ').unwrap_or(&doc).to_string())
+            }
+            _ => None,
+        })
+        .collect();
+    if docs.is_empty() {
+        None
+    } else {
+        Some(docs)
+    }
+}
+
 pub fn tts_to_string<T: quote::ToTokens>(item: T) -> String {
     let mut tts = proc_macro2::TokenStream::new();
     item.to_tokens(&mut tts);
@@ -3,6 +3,7 @@ use anchor_lang::solana_program::account_info::AccountInfo;
 use anchor_lang::solana_program::entrypoint::ProgramResult;
 use anchor_lang::solana_program::program_error::ProgramError;
 use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::solana_program::pubkey::Pubkey;
 use anchor_lang::{Accounts, CpiContext};
 use std::ops::Deref;
 
@@ -145,6 +146,12 @@ impl anchor_lang::AccountDeserialize for TokenAccount {
     }
 }
 
+impl anchor_lang::Owner for TokenAccount {
+    fn owner() -> Option<Pubkey> {
+        Some(ID)
+    }
+}
+
 impl Deref for TokenAccount {
     type Target = spl_token::state::Account;
 
@@ -166,6 +173,12 @@ impl anchor_lang::AccountDeserialize for Mint {
     }
 }
 
+impl anchor_lang::Owner for Mint {
+    fn owner() -> Option<Pubkey> {
+        Some(ID)
+    }
+}
+
 impl Deref for Mint {
     type Target = spl_token::state::Mint;
 
@@ -173,3 +186,13 @@ impl Deref for Mint {
         &self.0
     }
 }
+
+/// The SPL token program, for use as `Program<'info, Token>`.
+#[derive(Clone)]
+pub struct Token;
+
+impl anchor_lang::Id for Token {
+    fn id() -> Pubkey {
+        ID
+    }
+}
@@ -1,6 +1,6 @@
 import camelCase from "camelcase";
 import { snakeCase } from "snake-case";
-import { Layout } from "buffer-layout";
+import { Layout, seq } from "buffer-layout";
 import * as sha256 from "js-sha256";
 import * as borsh from "@project-serum/borsh";
 import {
@@ -250,6 +250,12 @@ class IdlCoder {
       case "i128": {
         return borsh.i128(fieldName);
       }
+      case "f32": {
+        return borsh.f32(fieldName);
+      }
+      case "f64": {
+        return borsh.f64(fieldName);
+      }
       case "bytes": {
         return borsh.vecU8(fieldName);
       }
@@ -288,6 +294,24 @@ class IdlCoder {
             fieldName
           );
           // @ts-ignore
+        } else if (field.type.array) {
+          // @ts-ignore
+          const [elemType, len] = field.type.array;
+          return seq(
+            IdlCoder.fieldLayout({ name: undefined, type: elemType }, types),
+            len,
+            fieldName
+          );
+          // @ts-ignore
+        } else if (field.type.tuple) {
+          // Tuples are encoded as structs keyed by the element's index.
+          // @ts-ignore
+          const elemLayouts = field.type.tuple.map(
+            (ty: IdlType, idx: number) =>
+              IdlCoder.fieldLayout({ name: `${idx}`, type: ty }, types)
+          );
+          return borsh.struct(elemLayouts, fieldName);
+          // @ts-ignore
         } else if (field.type.defined) {
           // User defined type.
           if (types === undefined) {
@@ -299,6 +323,26 @@ class IdlCoder {
             throw new IdlError(`Type not found: ${JSON.stringify(field)}`);
           }
           return IdlCoder.typeDefLayout(filtered[0], types, fieldName);
+          // @ts-ignore
+        } else if (field.type.definedWithGenerics) {
+          // User defined generic type.
+          if (types === undefined) {
+            throw new IdlError("User defined types not provided");
+          }
+          // @ts-ignore
+          const { name, generics } = field.type.definedWithGenerics;
+          const filtered = types.filter((t) => t.name === name);
+          if (filtered.length !== 1) {
+            throw new IdlError(`Type not found: ${JSON.stringify(field)}`);
+          }
+          return IdlCoder.typeDefLayout(
+            instantiateTypeDef(filtered[0], generics),
+            types,
+            fieldName
+          );
+          // @ts-ignore
+        } else if (field.type.generic) {
+          throw new IdlError(`Unresolved generic: ${JSON.stringify(field)}`);
         } else {
           throw new Error(`Not yet implemented: ${field}`);
         }
@@ -312,11 +356,20 @@ class IdlCoder {
     name?: string
   ): Layout {
     if (typeDef.type.kind === "struct") {
-      const fieldLayouts = typeDef.type.fields.map((field) => {
+      // @ts-ignore
+      const fieldLayouts = typeDef.type.fields.map((field: IdlField) => {
         const x = IdlCoder.fieldLayout(field, types);
         return x;
       });
       return borsh.struct(fieldLayouts, name);
+    } else if (typeDef.type.kind === "tuple") {
+      // Tuple structs are encoded as structs keyed by the field's index.
+      // @ts-ignore
+      const fieldLayouts = typeDef.type.fields.map(
+        (ty: IdlType, idx: number) =>
+          IdlCoder.fieldLayout({ name: `${idx}`, type: ty }, types)
+      );
+      return borsh.struct(fieldLayouts, name);
     } else if (typeDef.type.kind === "enum") {
       let variants = typeDef.type.variants.map((variant: IdlEnumVariant) => {
         const name = camelCase(variant.name);
@@ -382,6 +435,10 @@ function typeSize(idl: Idl, ty: IdlType): number {
       return 16;
     case "i128":
       return 16;
+    case "f32":
+      return 4;
+    case "f64":
+      return 8;
     case "bytes":
       return 1;
     case "string":
@@ -399,6 +456,20 @@ function typeSize(idl: Idl, ty: IdlType): number {
         return 1 + typeSize(idl, ty.option);
       }
       // @ts-ignore
+      if (ty.array !== undefined) {
+        // @ts-ignore
+        const [elemType, len] = ty.array;
+        return typeSize(idl, elemType) * len;
+      }
+      // @ts-ignore
+      if (ty.tuple !== undefined) {
+        // @ts-ignore
+        return ty.tuple.reduce(
+          (size: number, elemType: IdlType) => size + typeSize(idl, elemType),
+          0
+        );
+      }
+      // @ts-ignore
       if (ty.defined !== undefined) {
         // @ts-ignore
         const filtered = idl.types.filter((t) => t.name === ty.defined);
@@ -409,6 +480,16 @@ function typeSize(idl: Idl, ty: IdlType): number {
 
         return accountSize(idl, typeDef);
       }
+      // @ts-ignore
+      if (ty.definedWithGenerics !== undefined) {
+        // @ts-ignore
+        const { name, generics } = ty.definedWithGenerics;
+        const filtered = idl.types.filter((t) => t.name === name);
+        if (filtered.length !== 1) {
+          throw new IdlError(`Type not found: ${JSON.stringify(ty)}`);
+        }
+        return accountSize(idl, instantiateTypeDef(filtered[0], generics));
+      }
       throw new Error(`Invalid type ${JSON.stringify(ty)}`);
   }
 }
@@ -444,9 +525,94 @@ export function accountSize(
   if (idlAccount.type.fields === undefined) {
     return 0;
   }
-  return idlAccount.type.fields
-    .map((f) => typeSize(idl, f.type))
-    .reduce((a, b) => a + b);
+  if (idlAccount.type.kind === "tuple") {
+    return (
+      idlAccount.type.fields
+        // @ts-ignore
+        .map((ty: IdlType) => typeSize(idl, ty))
+        .reduce((a: number, b: number) => a + b, 0)
+    );
+  }
+  return (
+    idlAccount.type.fields
+      // @ts-ignore
+      .map((f: IdlField) => typeSize(idl, f.type))
+      .reduce((a: number, b: number) => a + b, 0)
+  );
+}
+
+// Returns the type definition with its generic type parameters replaced by
+// the given type arguments.
+function instantiateTypeDef(
+  typeDef: IdlTypeDef,
+  args: IdlType[]
+): IdlTypeDef {
+  const params = typeDef.type.generics || [];
+  if (params.length !== args.length) {
+    throw new IdlError(`Invalid type arguments for ${typeDef.name}`);
+  }
+  const substitute = (ty: IdlType): IdlType => {
+    if (typeof ty === "string") {
+      return ty;
+    }
+    // @ts-ignore
+    if (ty.generic !== undefined) {
+      // @ts-ignore
+      return args[params.indexOf(ty.generic)];
+    }
+    // @ts-ignore
+    if (ty.vec !== undefined) {
+      // @ts-ignore
+      return { vec: substitute(ty.vec) };
+    }
+    // @ts-ignore
+    if (ty.option !== undefined) {
+      // @ts-ignore
+      return { option: substitute(ty.option) };
+    }
+    // @ts-ignore
+    if (ty.array !== undefined) {
+      // @ts-ignore
+      return { array: [substitute(ty.array[0]), ty.array[1]] };
+    }
+    // @ts-ignore
+    if (ty.tuple !== undefined) {
+      // @ts-ignore
+      return { tuple: ty.tuple.map(substitute) };
+    }
+    // @ts-ignore
+    if (ty.definedWithGenerics !== undefined) {
+      return {
+        definedWithGenerics: {
+          // @ts-ignore
+          name: ty.definedWithGenerics.name,
+          // @ts-ignore
+          generics: ty.definedWithGenerics.generics.map(substitute),
+        },
+      };
+    }
+    return ty;
+  };
+  const substituteField = (f: IdlField | IdlType) =>
+    // @ts-ignore
+    f.name === undefined
+      ? substitute(f as IdlType)
+      : { ...(f as IdlField), type: substitute((f as IdlField).type) };
+  return {
+    name: typeDef.name,
+    type: {
+      kind: typeDef.type.kind,
+      // @ts-ignore
+      fields: typeDef.type.fields && typeDef.type.fields.map(substituteField),
+      variants:
+        typeDef.type.variants &&
+        typeDef.type.variants.map((variant) => ({
+          name: variant.name,
+          // @ts-ignore
+          fields: variant.fields && variant.fields.map(substituteField),
+        })),
+    },
+  };
 }
 
 // Not technically sighash, since we don't include the arguments, as Rust
@@ -8,11 +8,18 @@ export type Idl = {
   state?: IdlState;
   accounts?: IdlTypeDef[];
   types?: IdlTypeDef[];
+  events?: IdlEvent[];
   errors?: IdlErrorCode[];
 };
 
+export type IdlEvent = {
+  name: string;
+  fields: IdlField[];
+};
+
 export type IdlInstruction = {
   name: string;
+  docs?: string[];
   accounts: IdlAccountItem[];
   args: IdlField[];
 };
@@ -32,6 +39,38 @@ export type IdlAccount = {
   name: string;
   isMut: boolean;
   isSigner: boolean;
+  docs?: string[];
+  pda?: IdlPda;
+  // Accounts stored in this account's data, given by `belongs_to`.
+  relations?: string[];
+  // Owner of the account, given by an `owner` constraint.
+  owner?: "program" | "skip";
+};
+
+// Seeds of a program derived address. If `bump` is false, the bump seed is
+// given as the last seed. Otherwise, it's the canonical bump.
+export type IdlPda = {
+  seeds: IdlSeed[];
+  bump: boolean;
+};
+
+export type IdlSeed = IdlSeedConst | IdlSeedAccount | IdlSeedExpr;
+
+export type IdlSeedConst = {
+  kind: "const";
+  value: number[];
+};
+
+// Address of another account in the same accounts struct.
+export type IdlSeedAccount = {
+  kind: "account";
+  path: string;
+};
+
+// Seed that can't be resolved statically, as written in the program source.
+export type IdlSeedExpr = {
+  kind: "expr";
+  expr: string;
 };
 
 // A nested/recursive version of IdlAccount.
@@ -43,16 +82,22 @@ export type IdlAccounts = {
 export type IdlField = {
   name: string;
   type: IdlType;
+  docs?: string[];
 };
 
 export type IdlTypeDef = {
   name: string;
+  docs?: string[];
   type: IdlTypeDefTy;
+  // Set for accounts whose data is the struct's `#[repr(C)]` layout.
+  zeroCopy?: boolean;
 };
 
 type IdlTypeDefTy = {
-  kind: "struct" | "enum";
-  fields?: IdlTypeDefStruct;
+  kind: "struct" | "tuple" | "enum";
+  // Names of the type parameters of a generic type.
+  generics?: string[];
+  fields?: IdlTypeDefStruct | IdlType[];
   variants?: IdlEnumVariant[];
 };
 
@@ -68,12 +113,20 @@ export type IdlType =
   | "i32"
   | "u64"
   | "i64"
+  | "u128"
+  | "i128"
+  | "f32"
+  | "f64"
   | "bytes"
   | "string"
   | "publicKey"
   | IdlTypeVec
   | IdlTypeOption
-  | IdlTypeDefined;
+  | IdlTypeArray
+  | IdlTypeTuple
+  | IdlTypeDefined
+  | IdlTypeDefinedWithGenerics
+  | IdlTypeGeneric;
 
 export type IdlTypeVec = {
   vec: IdlType;
@@ -83,11 +136,33 @@ export type IdlTypeOption = {
   option: IdlType;
 };
 
+// Fixed size array of `[type, length]`.
+export type IdlTypeArray = {
+  array: [IdlType, number];
+};
+
+export type IdlTypeTuple = {
+  tuple: IdlType[];
+};
+
 // User defined type.
 export type IdlTypeDefined = {
   defined: string;
 };
 
+// User defined generic type, instantiated with the given type arguments.
+export type IdlTypeDefinedWithGenerics = {
+  definedWithGenerics: {
+    name: string;
+    generics: IdlType[];
+  };
+};
+
+// Type parameter of the enclosing generic type definition.
+export type IdlTypeGeneric = {
+  generic: string;
+};
+
 export type IdlEnumVariant = {
   name: string;
   fields?: IdlEnumFields;
@@ -103,6 +178,7 @@ type IdlErrorCode = {
   code: number;
   name: string;
   msg?: string;
+  docs?: string[];
 };
 
 // Deterministic IDL address as a function of the program id.